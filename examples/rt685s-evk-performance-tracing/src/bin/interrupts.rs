@@ -24,7 +24,7 @@ async fn main(_spawner: Spawner) {
         ChannelConfig::single_ended(p.PIO0_5),
         ChannelConfig::single_ended(p.PIO0_6),
     ];
-    let mut adc = Adc::new(p.ADC0, Irqs, Config::default(), channel_config);
+    let mut adc = Adc::new(p.ADC0, Irqs, Config::default(), channel_config).unwrap();
 
     loop {
         let mut data: [i16; 2] = [0; 2];
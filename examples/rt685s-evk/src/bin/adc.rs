@@ -19,7 +19,7 @@ async fn main(_spawner: Spawner) {
         ChannelConfig::single_ended_with_average(p.PIO0_5, Average::_16),
         ChannelConfig::single_ended(p.PIO0_6),
     ];
-    let mut adc = Adc::new(p.ADC0, Irqs, Config::default(), channel_config);
+    let mut adc = Adc::new(p.ADC0, Irqs, Config::default(), channel_config).unwrap();
 
     loop {
         let mut data: [i16; 2] = [0; 2];
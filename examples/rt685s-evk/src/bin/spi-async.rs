@@ -18,7 +18,7 @@ async fn main(_spawner: Spawner) {
 
     info!("Initializing SPI");
 
-    let mut spi = Spi::new_async(p.FLEXCOMM5, p.PIO1_3, p.PIO1_5, p.PIO1_4, Irqs, Default::default());
+    let mut spi = Spi::new_async(p.FLEXCOMM5, p.PIO1_3, p.PIO1_5, p.PIO1_4, Irqs, Default::default()).unwrap();
 
     let mut rxbuf = [0x55; 256];
     let txbuf = [0xaa; 256];
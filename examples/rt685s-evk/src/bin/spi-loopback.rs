@@ -12,7 +12,7 @@ async fn main(_spawner: Spawner) {
 
     info!("Initializing SPI");
 
-    let mut spi = Spi::new_blocking_loopback(p.FLEXCOMM5, Default::default());
+    let mut spi = Spi::new_blocking_loopback(p.FLEXCOMM5, Default::default()).unwrap();
 
     let mut rxbuf = [0; 256];
     let txbuf = [0xaa; 256];
@@ -24,7 +24,7 @@ async fn main(_spawner: Spawner) {
         gpio::SlewRate::Standard,
     );
 
-    let spi = Spi::new_blocking(p.FLEXCOMM5, p.PIO1_3, p.PIO1_5, p.PIO1_4, Default::default());
+    let spi = Spi::new_blocking(p.FLEXCOMM5, p.PIO1_3, p.PIO1_5, p.PIO1_4, Default::default()).unwrap();
     let delay = Delay;
 
     // One SPI device only on the SPI bus
@@ -10,7 +10,9 @@ use embassy_hal_internal::interrupt::InterruptExt;
 use embassy_hal_internal::{Peri, PeripheralType, impl_peripheral};
 use embassy_sync::waitqueue::AtomicWaker;
 
-use crate::clocks::enable_and_reset;
+use crate::clocks::{
+    ClkDivider, Clocks, Divider, FfroConfig, LposcFreq, SFRO_FREQ, check_fclk, enable_and_reset_at, ensure_running,
+};
 use crate::interrupt::typelevel::Binding;
 use crate::iopctl::{DriveMode, DriveStrength, Function, Inverter, IopctlPin, Pull, SlewRate};
 use crate::pac::adc0;
@@ -21,17 +23,39 @@ use crate::{interrupt, peripherals};
 static WAKER: AtomicWaker = AtomicWaker::new();
 
 /// ADC error
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum Error {
     /// Invalid ADC configuration
     InvalidConfig,
+    /// Configured clock source not running, or the resulting function clock outside the ADC's
+    /// rated window
+    InvalidClock,
+}
+
+/// ADC function clock source
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClockSource {
+    /// 1 MHz low power oscillator
+    Lposc,
+    /// 16 MHz free running oscillator
+    Sfro,
+    /// 48/60 MHz free running oscillator
+    Ffro,
+    /// `aux1_pll_clk` running at the given rate, see [`crate::clocks::aux1_pll_clk_rate`]
+    Aux1Pll(u32),
 }
 
 /// ADC config
 pub struct Config {
     /// ADC voltage reference
     pub vref: Reference,
+    /// ADC function clock source
+    pub clock: ClockSource,
+    /// Divider applied to `clock`, the result has to be within the ADC's rating
+    pub clock_div: Divider,
 }
 
 impl Default for Config {
@@ -39,6 +63,8 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             vref: Reference::VddaAdc1v8,
+            clock: ClockSource::Lposc,
+            clock_div: Divider::ONE,
         }
     }
 }
@@ -156,7 +182,7 @@ struct Info {
 }
 
 impl<const N: usize> Adc<'_, N> {
-    fn init() {
+    fn init(config: &Config) -> Result<(), Error> {
         let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
         let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
 
@@ -166,18 +192,38 @@ impl<const N: usize> Adc<'_, N> {
             .write(|w| w.adc_pd().set_bit().adc_lp().set_bit());
 
         // Configure ADC clock mux
-        // Select LPOSC for now, unless we want to speed up the clocks
-        clkctl0.adc0fclksel0().write(|w| w.sel().lposc());
-        clkctl0.adc0fclksel1().write(|w| w.sel().adc0fclksel0_mux_out());
+        let src_freq = match config.clock {
+            ClockSource::Lposc => {
+                clkctl0.adc0fclksel0().write(|w| w.sel().lposc());
+                LposcFreq::Lp1m.hz()
+            }
+            ClockSource::Sfro => {
+                ensure_running(Clocks::Sfro).map_err(|_| Error::InvalidClock)?;
+                clkctl0.adc0fclksel0().write(|w| w.sel().sfro_clk());
+                SFRO_FREQ
+            }
+            ClockSource::Ffro => {
+                ensure_running(Clocks::Ffro).map_err(|_| Error::InvalidClock)?;
+                clkctl0.adc0fclksel0().write(|w| w.sel().ffro_clk());
+                FfroConfig::trimmed_freq().hz()
+            }
+            ClockSource::Aux1Pll(rate) => rate,
+        };
+
+        // conversions outside the rated window are wrong rather than failing, so check before
+        // switching the mux over and refuse to enable
+        let fclk = config.clock_div.divide(src_freq);
+        check_fclk::<ADC0>(fclk).map_err(|_| Error::InvalidClock)?;
+
+        match config.clock {
+            ClockSource::Aux1Pll(_) => clkctl0.adc0fclksel1().write(|w| w.sel().syspll0_aux1_pll_clock()),
+            _ => clkctl0.adc0fclksel1().write(|w| w.sel().adc0fclksel0_mux_out()),
+        };
 
         // Set ADC clock divisor
-        clkctl0.adc0fclkdiv().modify(|_, w| w.reset().set_bit());
-        clkctl0
-            .adc0fclkdiv()
-            .write(|w| unsafe { w.div().bits(0x0).halt().clear_bit() });
-        while clkctl0.adc0fclkdiv().read().reqflag().bit_is_set() {}
+        clkctl0.adc0fclkdiv().set_div(config.clock_div);
 
-        enable_and_reset::<ADC0>();
+        enable_and_reset_at::<ADC0>(fclk).map_err(|_| Error::InvalidClock)
     }
 
     fn configure_adc(&mut self, config: Config) {
@@ -281,18 +327,21 @@ impl<const N: usize> Adc<'_, N> {
 
 impl<'p, const N: usize> Adc<'p, N> {
     /// Create ADC driver.
+    ///
+    /// Errors with [`Error::InvalidClock`] if the configured clock source isn't running or the
+    /// resulting ADC function clock is outside its rating.
     pub fn new<T: Instance>(
         _adc: Peri<'p, T>,
         _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'p,
         config: Config,
         channel_config: [ChannelConfig; N],
-    ) -> Self {
+    ) -> Result<Self, Error> {
         let mut inst = Self {
             info: T::info(),
             _lifetime: PhantomData,
         };
 
-        Self::init();
+        Self::init(&config)?;
         inst.configure_adc(config);
         inst.configure_channels(&channel_config);
 
@@ -300,7 +349,7 @@ impl<'p, const N: usize> Adc<'p, N> {
         interrupt::ADC0.unpend();
         unsafe { interrupt::ADC0.enable() };
 
-        inst
+        Ok(inst)
     }
 
     /// One shot sampling. The buffer must be the same size as the number of channels configured.
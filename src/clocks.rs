@@ -1,8 +1,13 @@
 //! Clock configuration for the `RT6xx`
-use core::sync::atomic::{AtomicU8, AtomicU32, Ordering};
+//!
+//! Halting the core in the debugger doesn't gate any clocks on this part, the `RT6xx` has no
+//! debug clock-enable control. Peripheral clocks (PWM, flexcomm, timers) keep running across
+//! breakpoints, only the low power modes stop them.
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicU32, Ordering};
 
 #[cfg(feature = "defmt")]
 use defmt;
+use embassy_hal_internal::{Peri, PeripheralType};
 use paste::paste;
 
 use crate::pac;
@@ -63,27 +68,29 @@ pub struct ClockConfig {
 
 impl ClockConfig {
     /// Clock configuration derived from external crystal.
+    ///
+    /// This is a `const fn` so the configuration can be built in `const` and `static` contexts.
     #[must_use]
-    pub fn crystal() -> Self {
+    pub const fn crystal() -> Self {
         const CORE_CPU_FREQ: u32 = 500_000_000;
         const PLL_CLK_FREQ: u32 = 528_000_000;
         const SYS_CLK_FREQ: u32 = CORE_CPU_FREQ / 2;
         Self {
             lposc: LposcConfig {
                 state: State::Enabled,
-                freq: AtomicU32::new(Into::into(LposcFreq::Lp1m)),
+                freq: AtomicU32::new(LposcFreq::Lp1m.hz()),
             },
             sfro: SfroConfig { state: State::Enabled },
             rtc: RtcClkConfig {
                 state: State::Enabled,
                 wake_alarm_state: State::Disabled,
                 sub_second_state: State::Disabled,
-                freq: AtomicU32::new(Into::into(RtcFreq::Default1Hz)),
+                freq: AtomicU32::new(RtcFreq::Default1Hz.hz()),
                 rtc_int: RtcInterrupts::None,
             },
             ffro: FfroConfig {
                 state: State::Enabled,
-                freq: AtomicU32::new(Into::into(FfroFreq::Ffro48m)),
+                freq: AtomicU32::new(FfroFreq::Ffro48m.hz()),
             },
             //pll: Some(PllConfig {}),//includes aux0 and aux1 pll
             clk_in: ClkInConfig {
@@ -120,6 +127,177 @@ impl ClockConfig {
     }
 }
 
+impl ClockConfig {
+    /// Range checks the configuration without touching hardware.
+    ///
+    /// Checks the PFD dividers, the main clock divider and the oscillator frequencies,
+    /// returning the error for the first field out of range. This only catches a bad
+    /// configuration early, e.g. in a test; init still checks everything it programs.
+    pub fn validate(&self) -> Result<(), ClockError> {
+        if self.main_pll_clk.is_enabled() {
+            self.check_pll()?;
+        }
+        self.main_clk.ahb_div()?;
+        FfroFreq::try_from(self.ffro.freq.load(Ordering::Relaxed))?;
+        LposcFreq::try_from(self.lposc.freq.load(Ordering::Relaxed))?;
+        Ok(())
+    }
+
+    /// Returns whether anything in this configuration consumes the 32 kHz crystal oscillator.
+    ///
+    /// The RTC itself, a main clock on `32k_clk` and the RTC time driver need it. Without such a
+    /// consumer [`crate::init`] leaves the oscillator off, avoiding the crystal's startup
+    /// current on power-sensitive boots, and a later user has to enable the RTC clock itself.
+    #[must_use]
+    pub fn needs_32k_osc(&self) -> bool {
+        self.rtc.state == State::Enabled || self.main_clk.src == MainClkSrc::RTC32k || cfg!(feature = "time-driver-rtc")
+    }
+
+    /// Range checks the PFD dividers brought up with the PLL.
+    ///
+    /// `mult` isn't checked: init always brings the PLL up at x22, see `init_syspll`, and only
+    /// [`ConfigurableClock::set_clock_rate`] programs a different multiplier.
+    fn check_pll(&self) -> Result<(), ClockError> {
+        for pfd in [PllPfd::Pfd0, PllPfd::Pfd2] {
+            if !MainPllClkConfig::PFD_DIV_RANGE.contains(&self.main_pll_clk.pfd_bits(pfd)) {
+                return Err(ClockError::InvalidDiv);
+            }
+        }
+        Ok(())
+    }
+
+    /// Walks the clock dependency graph, checking every selected clock's upstream chain.
+    ///
+    /// Starting from the system clock (and the main PLL, if enabled), each clock's source is
+    /// followed up to an oscillator or clk_in. Every link must be enabled in the config and in
+    /// range. Returns the first broken link, naming both the clock and the source it can't use,
+    /// which pinpoints a misconfiguration better than a bare [`ClockError`].
+    pub fn validate_graph(&self) -> Result<(), BrokenLink> {
+        let mut roots = [Some(Clocks::SysClk), None];
+        if self.main_pll_clk.is_enabled() {
+            roots[1] = Some(Clocks::MainPllClk);
+        }
+
+        for mut clock in roots.into_iter().flatten() {
+            while let Some(source) = self.upstream(clock) {
+                if let Err(reason) = self.check_node(source) {
+                    return Err(BrokenLink { clock, source, reason });
+                }
+                clock = source;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the clock `clock` is fed from in this configuration, `None` for a root clock
+    fn upstream(&self, clock: Clocks) -> Option<Clocks> {
+        match clock {
+            Clocks::SysClk => Some(Clocks::Hclk),
+            Clocks::Hclk => Some(Clocks::MainClk),
+            Clocks::MainClk => Some(self.main_clk.src.into()),
+            // init brings the PLL up from 48/60m_irc_div2, see `init_syspll`
+            Clocks::MainPllClk => Some(Clocks::Ffro),
+            _ => None,
+        }
+    }
+
+    /// Checks that `clock` is enabled in this configuration and its settings are in range
+    fn check_node(&self, clock: Clocks) -> Result<(), ClockError> {
+        let enabled = match clock {
+            Clocks::Lposc => self.lposc.is_enabled(),
+            Clocks::Sfro => self.sfro.is_enabled(),
+            Clocks::Rtc => self.rtc.is_enabled(),
+            Clocks::Ffro => self.ffro.is_enabled(),
+            Clocks::ClkIn => self.clk_in.clk_in_hz().is_some(),
+            Clocks::MainClk => self.main_clk.is_enabled(),
+            Clocks::MainPllClk => self.main_pll_clk.is_enabled(),
+            Clocks::SysOscClk => self.sys_osc.state == State::Enabled,
+            Clocks::Hclk | Clocks::SysClk | Clocks::Adc => true,
+        };
+        if !enabled {
+            return Err(ClockError::ClockNotEnabled);
+        }
+
+        match clock {
+            Clocks::Lposc => LposcFreq::try_from(self.lposc.freq.load(Ordering::Relaxed)).map(drop),
+            Clocks::Ffro => FfroFreq::try_from(self.ffro.freq.load(Ordering::Relaxed)).map(drop),
+            Clocks::MainPllClk => self.check_pll(),
+            Clocks::Hclk => self.main_clk.ahb_div().map(drop),
+            _ => Ok(()),
+        }
+    }
+
+    /// Computes the clock rates this configuration would produce, without touching hardware.
+    ///
+    /// Runs the same range checks as [`ClockConfig::validate`], then derives every rate from
+    /// the config alone, so it can run on the host or in a tool previewing a configuration.
+    /// Errors with [`ClockError::ClockNotEnabled`] if the main clock is fed from a disabled
+    /// clock.
+    pub fn simulate(&self) -> Result<ClockSummary, ClockError> {
+        self.validate()?;
+
+        let enabled = |on: bool, hz: u32| if on { hz } else { 0 };
+        let ffro_hz = enabled(self.ffro.is_enabled(), self.ffro.freq.load(Ordering::Relaxed));
+        let sfro_hz = enabled(self.sfro.is_enabled(), SFRO_FREQ);
+        let lposc_hz = enabled(self.lposc.is_enabled(), self.lposc.freq.load(Ordering::Relaxed));
+        let clk_in_hz = self.clk_in.clk_in_hz().unwrap_or(0);
+        let rtc_32k_hz = enabled(self.rtc.is_enabled(), RtcFreq::SubSecond32kHz.hz());
+
+        let main_pll_hz = if self.main_pll_clk.is_enabled() {
+            if ffro_hz == 0 {
+                return Err(ClockError::ClockNotEnabled);
+            }
+            // init brings the PLL up from 48/60m_irc_div2 x 22, see `init_syspll`
+            let vco = MainPllClkConfig::check_fractional_mult(ffro_hz / 2, 22, 0, 1)?;
+            MainPllClkConfig::pfd_freq(u64::from(vco), self.main_pll_clk.pfd_bits(PllPfd::Pfd0))?
+        } else {
+            0
+        };
+
+        let main_clk_hz = match self.main_clk.src {
+            MainClkSrc::PllMain => main_pll_hz,
+            MainClkSrc::FFRO => ffro_hz,
+            MainClkSrc::FFROdiv4 => ffro_hz / 4,
+            MainClkSrc::SFRO => sfro_hz,
+            MainClkSrc::Lposc => lposc_hz,
+            MainClkSrc::ClkIn => clk_in_hz,
+            MainClkSrc::RTC32k => rtc_32k_hz,
+        };
+        if main_clk_hz == 0 {
+            return Err(ClockError::ClockNotEnabled);
+        }
+
+        Ok(ClockSummary {
+            main_clk_hz,
+            hclk_hz: self.main_clk.ahb_div()?.divide(main_clk_hz),
+            main_pll_hz,
+            ffro_hz,
+            sfro_hz,
+            lposc_hz,
+            clk_in_hz,
+            rtc_32k_hz,
+        })
+    }
+}
+
+/// A broken link in the clock dependency chain, see [`ClockConfig::validate_graph`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BrokenLink {
+    /// Clock whose source is unusable
+    pub clock: Clocks,
+    /// Source `clock` depends on
+    pub source: Clocks,
+    /// Why `source` can't feed `clock`
+    pub reason: ClockError,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self::crystal()
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 /// Clock state enum
@@ -136,25 +314,36 @@ pub enum State {
 pub enum LposcFreq {
     /// 1 `MHz` oscillator
     Lp1m,
-    /// 32kHz oscillator
+    /// `lp_32k`, the 1 `MHz` oscillator divided by 32.
+    ///
+    /// Despite the name this runs at 31.25 kHz, not 32.768 kHz. Use the RTC oscillator when an
+    /// accurate 32.768 kHz timebase is needed.
     Lp32k,
 }
 
-impl From<LposcFreq> for u32 {
-    fn from(value: LposcFreq) -> Self {
-        match value {
+impl LposcFreq {
+    /// Frequency in Hz
+    #[must_use]
+    pub const fn hz(self) -> u32 {
+        match self {
             LposcFreq::Lp1m => 1_000_000,
-            LposcFreq::Lp32k => 32_768,
+            LposcFreq::Lp32k => 31_250,
         }
     }
 }
 
+impl From<LposcFreq> for u32 {
+    fn from(value: LposcFreq) -> Self {
+        value.hz()
+    }
+}
+
 impl TryFrom<u32> for LposcFreq {
     type Error = ClockError;
     fn try_from(value: u32) -> Result<Self, Self::Error> {
         match value {
             1_000_000 => Ok(LposcFreq::Lp1m),
-            32_768 => Ok(LposcFreq::Lp32k),
+            31_250 => Ok(LposcFreq::Lp32k),
             _ => Err(ClockError::InvalidFrequency),
         }
     }
@@ -167,7 +356,7 @@ pub struct LposcConfig {
     freq: AtomicU32,
 }
 
-const SFRO_FREQ: u32 = 16_000_000;
+pub(crate) const SFRO_FREQ: u32 = 16_000_000;
 /// SFRO config
 pub struct SfroConfig {
     state: State,
@@ -183,9 +372,11 @@ pub enum RtcFreq {
     SubSecond32kHz,
 }
 
-impl From<RtcFreq> for u32 {
-    fn from(value: RtcFreq) -> Self {
-        match value {
+impl RtcFreq {
+    /// Frequency in Hz
+    #[must_use]
+    pub const fn hz(self) -> u32 {
+        match self {
             RtcFreq::Default1Hz => 1,
             RtcFreq::HighResolution1khz => 1_000,
             RtcFreq::SubSecond32kHz => 32_768,
@@ -193,6 +384,12 @@ impl From<RtcFreq> for u32 {
     }
 }
 
+impl From<RtcFreq> for u32 {
+    fn from(value: RtcFreq) -> Self {
+        value.hz()
+    }
+}
+
 impl TryFrom<u32> for RtcFreq {
     type Error = ClockError;
     fn try_from(value: u32) -> Result<Self, Self::Error> {
@@ -254,15 +451,23 @@ pub struct FfroConfig {
     freq: AtomicU32,
 }
 
-impl From<FfroFreq> for u32 {
-    fn from(value: FfroFreq) -> Self {
-        match value {
+impl FfroFreq {
+    /// Frequency in Hz
+    #[must_use]
+    pub const fn hz(self) -> u32 {
+        match self {
             FfroFreq::Ffro48m => 48_000_000,
             FfroFreq::Ffro60m => 60_000_000,
         }
     }
 }
 
+impl From<FfroFreq> for u32 {
+    fn from(value: FfroFreq) -> Self {
+        value.hz()
+    }
+}
+
 impl TryFrom<u32> for FfroFreq {
     type Error = ClockError;
     fn try_from(value: u32) -> Result<Self, Self::Error> {
@@ -310,6 +515,9 @@ impl TryFrom<Clocks> for MainPllClkSrc {
 }
 
 /// PLL configuration.
+///
+/// The RT6xx system PLL has no spread-spectrum modulation, only the fractional `NUM`/`DENOM`
+/// multiplier, so EMI reduction has to come from elsewhere (e.g. slew rate and drive strength).
 pub struct MainPllClkConfig {
     /// Clock active state
     pub state: State,
@@ -335,6 +543,75 @@ pub struct MainPllClkConfig {
     /// aux divider 1
     pub aux1_div: u8,
 }
+/// Main PLL fractional divider outputs.
+///
+/// Each PFD feeds exactly one PLL output clock, see [`MainPllClkConfig::pfd_bits`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PllPfd {
+    /// PFD0, feeds `main_pll_clk`
+    Pfd0,
+    /// PFD1, feeds `dsp_pll_clk`
+    Pfd1,
+    /// PFD2, feeds `aux0_pll_clk`
+    Pfd2,
+    /// PFD3, feeds `aux1_pll_clk`
+    Pfd3,
+}
+
+/// Closest main PLL setting for a requested output, see [`MainPllClkConfig::for_output`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PllPlan {
+    /// PLL multiplier
+    pub mult: u8,
+    /// Fractional multiplier numerator, for `SYSPLL0NUM`
+    pub num: u32,
+    /// Fractional multiplier denominator, for `SYSPLL0DENOM`
+    pub denom: u32,
+    /// PFD divider bits
+    pub pfd_div: u8,
+    /// Frequency the setting actually produces
+    pub achieved_hz: u32,
+    /// `achieved_hz - target_hz`, negative if the result is low
+    pub error_hz: i32,
+}
+
+impl PllPfd {
+    /// Returns the PFD divider giving an output of `num / den` times the VCO, if one exists.
+    ///
+    /// A PFD outputs `vco * 18 / div`, with `div` in `12..=35`, so it can both multiply up and
+    /// divide down:
+    ///
+    /// | div | ratio   | effect         |
+    /// |-----|---------|----------------|
+    /// | 12  | 3/2     | multiplies up  |
+    /// | 16  | 9/8     | multiplies up  |
+    /// | 18  | 1       | pass-through   |
+    /// | 24  | 3/4     | divides down   |
+    /// | 27  | 2/3     | divides down   |
+    /// | 36  | 1/2     | out of range   |
+    ///
+    /// Returns `None` if no integer divider in range gives exactly the requested ratio.
+    #[must_use]
+    pub const fn div_from_multiplier(num: u32, den: u32) -> Option<u8> {
+        let Some(scaled) = 18u32.checked_mul(den) else {
+            return None;
+        };
+        if num == 0 || !scaled.is_multiple_of(num) {
+            return None;
+        }
+        let div = scaled / num;
+        if div >= *MainPllClkConfig::PFD_DIV_RANGE.start() as u32
+            && div <= *MainPllClkConfig::PFD_DIV_RANGE.end() as u32
+        {
+            Some(div as u8)
+        } else {
+            None
+        }
+    }
+}
+
 /// External input clock config
 pub struct ClkInConfig {
     /// External clock input state
@@ -349,7 +626,70 @@ pub struct HclkConfig {
     pub state: State,
 }
 
+impl HclkConfig {
+    /// Returns the AHB clock rate for a main clock running at `main_clk_freq`.
+    ///
+    /// The divider is read back from `SYSCPUAHBCLKDIV` rather than taken from the config,
+    /// so this reflects the hardware even if the divider was changed outside of init.
+    #[must_use]
+    pub fn get_clock_rate(&self, main_clk_freq: u32) -> u32 {
+        syscpuahb_clk_div().divide(main_clk_freq)
+    }
+}
+
+/// A clock divider, holding the true division factor (1 = undivided, up to 256).
+///
+/// The `*DIV` registers encode the factor minus one. Keeping the factor here and converting
+/// only at the register boundary avoids mixing up the two encodings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Divider(u16);
+
+impl Divider {
+    /// Undivided
+    pub const ONE: Self = Self(1);
+    /// Largest factor an 8-bit divider field can encode
+    pub const MAX: Self = Self(256);
+
+    /// Creates a divider dividing by `factor`, which must be in `1..=256`
+    pub const fn new(factor: u16) -> Result<Self, ClockError> {
+        if factor == 0 || factor > Self::MAX.0 {
+            Err(ClockError::InvalidDiv)
+        } else {
+            Ok(Self(factor))
+        }
+    }
+
+    /// Creates a divider from the register encoding, `bits + 1`
+    #[must_use]
+    pub const fn from_reg_bits(bits: u8) -> Self {
+        Self(bits as u16 + 1)
+    }
+
+    /// Division factor
+    #[must_use]
+    pub const fn factor(self) -> u16 {
+        self.0
+    }
+
+    /// Register encoding, `factor - 1`
+    #[must_use]
+    pub const fn reg_bits(self) -> u8 {
+        (self.0 - 1) as u8
+    }
+
+    /// Divides `hz` by this divider
+    #[must_use]
+    pub const fn divide(self, hz: u32) -> u32 {
+        hz / self.0 as u32
+    }
+}
+
 /// Main clock source.
+///
+/// `MAINCLKSELA` only offers the FFRO undivided or divided by 4. The `48/60m_irc_div2` tap
+/// is routed to the main PLL input, not the main clock mux, so a 24 MHz main clock from a
+/// 48 MHz FFRO requires the AHB divider or the PLL.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum MainClkSrc {
@@ -364,11 +704,41 @@ pub enum MainClkSrc {
     /// SFRO
     SFRO,
     /// Main PLL Clock
+    ///
+    /// This is `main_pll_clk`, the PLL output behind PFD0, and the only PLL output the main clock
+    /// mux can select. `aux0_pll_clk`, `aux1_pll_clk` and `dsp_pll_clk` only feed peripheral
+    /// clock muxes, a main clock from them isn't possible.
     PllMain,
     /// RTC 32kHz oscillator.
+    ///
+    /// An extreme low power mode: the core, AHB and everything on the main clock run at
+    /// 32768 Hz. FlexSPI must not be fed from the main clock, or XIP code fetches slow to a crawl,
+    /// and the SWD clock has to stay well below the core clock, so most debug probes lose the
+    /// connection. Peripherals with a minimum function clock (e.g. the ADC) stop working if
+    /// clocked from the main clock.
     RTC32k,
 }
 
+impl MainClkSrc {
+    /// Returns the `MAINCLKSELA` and `MAINCLKSELB` selections routing this source to the main clock.
+    ///
+    /// Sources on the first stage mux go through `MAINCLKSELB`'s `main_1st_clk` input, the
+    /// others are selected by `MAINCLKSELB` alone and leave `MAINCLKSELA` untouched (`None`).
+    const fn mux_sel(self) -> (Option<pac::clkctl0::mainclksela::Sel>, pac::clkctl0::mainclkselb::Sel) {
+        use pac::clkctl0::mainclksela::Sel as SelA;
+        use pac::clkctl0::mainclkselb::Sel as SelB;
+        match self {
+            MainClkSrc::FFROdiv4 => (Some(SelA::FfroDiv4), SelB::Main1stClk),
+            MainClkSrc::ClkIn => (Some(SelA::SysxtalClk), SelB::Main1stClk),
+            MainClkSrc::Lposc => (Some(SelA::Lposc), SelB::Main1stClk),
+            MainClkSrc::FFRO => (Some(SelA::FfroClk), SelB::Main1stClk),
+            MainClkSrc::SFRO => (None, SelB::SfroClk),
+            MainClkSrc::PllMain => (None, SelB::MainPllClk),
+            MainClkSrc::RTC32k => (None, SelB::Rtc32kClk),
+        }
+    }
+}
+
 impl From<MainClkSrc> for Clocks {
     fn from(value: MainClkSrc) -> Self {
         match value {
@@ -399,6 +769,10 @@ impl TryFrom<Clocks> for MainClkSrc {
 }
 
 /// Main clock config.
+///
+/// The core supply voltage is not managed by this crate. The safe maximum main clock depends
+/// on it, so the core voltage must already be set for the configured rate (e.g. by the boot ROM
+/// or a PMIC driver) before raising the main clock, and only lowered after reducing it.
 pub struct MainClkConfig {
     /// Main clock state
     pub state: State,
@@ -432,8 +806,17 @@ pub enum ClockError {
     ClockMismatch,
     /// Error due to attempting to modify a clock that's not yet been enabled
     ClockNotEnabled,
+    /// Error due to using a clock that is enabled in the config, but not running in hardware yet
+    ///
+    /// Unlike [`ClockError::ClockNotEnabled`], which points at the [`ClockConfig`], this points
+    /// at init ordering: the clock was requested but hasn't been brought up.
+    ClockNotRunning,
     /// Error due to attempting to set a clock source that's not a supported option
     ClockNotSupported,
+    /// Error due to attempting to power down a clock that an active clock still depends on
+    ClockInUse,
+    /// Error due to attempting to reconfigure clocks after [`lock_clocks`]
+    Locked,
     /// Error due to attempting to set a clock to an invalid frequency
     InvalidFrequency,
     /// Error due to attempting to modify a clock output with an invalid divider
@@ -457,6 +840,12 @@ pub trait ConfigurableClock {
 }
 
 impl LposcConfig {
+    /// Returns the rate of `lp_32k` (1m_lposc / 32), or `None` if the LPOSC isn't running.
+    #[must_use]
+    pub fn lp_32k_rate(&self) -> Option<u32> {
+        (self.is_enabled() && is_clock_running(Clocks::Lposc)).then_some(LposcFreq::Lp32k.hz())
+    }
+
     /// Initializes low-power oscillator.
     fn init_lposc() {
         // Enable low power oscillator
@@ -477,27 +866,24 @@ impl ConfigurableClock for LposcConfig {
         Ok(())
     }
     fn disable(&self) -> Result<(), ClockError> {
-        // SAFETY: unsafe needed to take pointer to Sysctl0, needed to power down the LPOSC HW
-        let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
-        sysctl0.pdruncfg0_set().write(|w| w.lposc_pd().set_pdruncfg0());
-        // Wait until LPOSC disabled
-        while !sysctl0.pdruncfg0().read().lposc_pd().is_power_down() {}
-        Ok(())
+        power_down(Clocks::Lposc)
     }
     fn get_clock_rate(&self) -> Result<u32, ClockError> {
         Ok(self.freq.load(Ordering::Relaxed))
     }
     fn set_clock_rate(&mut self, _div: u8, _mult: u8, freq: u32) -> Result<(), ClockError> {
+        check_unlocked()?;
         if let Ok(r) = <u32 as TryInto<LposcFreq>>::try_into(freq) {
             match r {
                 LposcFreq::Lp1m => {
                     self.freq
-                        .store(LposcFreq::Lp1m as u32, core::sync::atomic::Ordering::Relaxed);
+                        .store(LposcFreq::Lp1m.hz(), core::sync::atomic::Ordering::Relaxed);
                     Ok(())
                 }
+                // lp_32k is a fixed tap of the 1 MHz oscillator, which keeps running at 1 MHz
                 LposcFreq::Lp32k => {
                     self.freq
-                        .store(LposcFreq::Lp1m as u32, core::sync::atomic::Ordering::Relaxed);
+                        .store(LposcFreq::Lp1m.hz(), core::sync::atomic::Ordering::Relaxed);
                     Ok(())
                 }
             }
@@ -526,81 +912,114 @@ impl FfroConfig {
 
         // No FFRO enable/disable control in CLKCTL.
         // Delay enough for FFRO to be stable in case it was just powered on
-        delay_loop_clocks(50, 12_000_000);
+        settle_delay(FFRO_STARTUP_US);
+    }
+
+    /// Program the FFRO trim range for `freq` without changing its power state.
+    ///
+    /// This allows trimming the FFRO once, e.g. to 60 MHz, while leaving it powered down
+    /// until a consumer needs it. Note that clock init always powers the FFRO up, since the
+    /// FlexSPI clock is moved to it while the PLL and main clock are reconfigured.
+    pub fn trim(freq: FfroFreq) {
+        // SAFETY: unsafe needed to take pointer to Clkctl0, needed to set the right HW frequency
+        let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+        clkctl0.ffroctl1().write(|w| w.update().update_safe_mode());
+        match freq {
+            FfroFreq::Ffro48m => {
+                clkctl0.ffroctl0().write(|w| w.trim_range().ffro_48mhz());
+            }
+            FfroFreq::Ffro60m => {
+                clkctl0.ffroctl0().write(|w| w.trim_range().ffro_60mhz());
+            }
+        }
+        clkctl0.ffroctl1().write(|w| w.update().normal_mode());
+    }
+
+    /// Rate of the `48/60m_irc_div2` tap, as seen by the main PLL when it selects the FFRO.
+    ///
+    /// Returns `None` if the FFRO is not running.
+    #[must_use]
+    pub fn div2_rate(&self) -> Option<u32> {
+        self.get_clock_rate().ok().map(|rate| rate / 2)
+    }
+
+    /// Rate of the `48/60m_irc_div4` tap, as seen by the main clock when it selects `FFROdiv4`.
+    ///
+    /// Returns `None` if the FFRO is not running.
+    #[must_use]
+    pub fn div4_rate(&self) -> Option<u32> {
+        self.get_clock_rate().ok().map(|rate| rate / 4)
+    }
+}
+
+impl FfroConfig {
+    /// Returns the frequency the FFRO is currently trimmed to in hardware
+    pub(crate) fn trimmed_freq() -> FfroFreq {
+        // SAFETY: unsafe needed to take pointer to Clkctl0, only used for a register read
+        let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+        if clkctl0.ffroctl0().read().trim_range().is_ffro_48mhz() {
+            FfroFreq::Ffro48m
+        } else {
+            FfroFreq::Ffro60m
+        }
     }
 }
 
 impl ConfigurableClock for FfroConfig {
     fn enable_and_reset(&self) -> Result<(), ClockError> {
+        // Program the trim before powering up, so the FFRO starts at the configured frequency
+        let freq = <u32 as TryInto<FfroFreq>>::try_into(self.freq.load(Ordering::Relaxed))?;
+        FfroConfig::trim(freq);
         // SAFETY: should be called once
         FfroConfig::init_ffro_clk();
-        // default is 48 MHz
         Ok(())
     }
     fn disable(&self) -> Result<(), ClockError> {
-        // SAFETY: unsafe needed to take pointer to Sysctl0, only to power down FFRO
-        let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
-        sysctl0.pdruncfg0_set().write(|w| w.ffro_pd().set_pdruncfg0());
-        delay_loop_clocks(30, 12_000_000);
-        // Wait until FFRO disabled
-        while !sysctl0.pdruncfg0().read().ffro_pd().is_power_down() {}
-        Ok(())
+        power_down(Clocks::Ffro)
     }
     fn get_clock_rate(&self) -> Result<u32, ClockError> {
-        Ok(self.freq.load(Ordering::Relaxed))
-    }
-    fn set_clock_rate(&mut self, _div: u8, _mult: u8, freq: u32) -> Result<(), ClockError> {
-        if let Ok(r) = <u32 as TryInto<FfroFreq>>::try_into(freq) {
-            match r {
-                FfroFreq::Ffro48m => {
-                    // SAFETY: unsafe needed to take pointer to Clkctl0, needed to set the right HW frequency
-                    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
-                    clkctl0.ffroctl1().write(|w| w.update().update_safe_mode());
-                    clkctl0.ffroctl0().write(|w| w.trim_range().ffro_48mhz());
-                    clkctl0.ffroctl1().write(|w| w.update().normal_mode());
-
-                    self.freq
-                        .store(FfroFreq::Ffro48m as u32, core::sync::atomic::Ordering::Relaxed);
-                    Ok(())
-                }
-                FfroFreq::Ffro60m => {
-                    // SAFETY: unsafe needed to take pointer to Clkctl0, needed to set the right HW frequency
-                    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
-                    clkctl0.ffroctl1().write(|w| w.update().update_safe_mode());
-                    clkctl0.ffroctl0().write(|w| w.trim_range().ffro_60mhz());
-                    clkctl0.ffroctl1().write(|w| w.update().normal_mode());
-
-                    self.freq
-                        .store(FfroFreq::Ffro60m as u32, core::sync::atomic::Ordering::Relaxed);
-                    Ok(())
-                }
-            }
+        if !self.is_enabled() {
+            return Err(ClockError::ClockNotEnabled);
+        }
+        // The trim may be programmed while the FFRO is powered down, only report a rate once it's running
+        if is_clock_running(Clocks::Ffro) {
+            Ok(self.freq.load(Ordering::Relaxed))
         } else {
-            Err(ClockError::InvalidFrequency)
+            Err(ClockError::ClockNotRunning)
         }
     }
+    fn set_clock_rate(&mut self, _div: u8, _mult: u8, freq: u32) -> Result<(), ClockError> {
+        check_unlocked()?;
+        let r = <u32 as TryInto<FfroFreq>>::try_into(freq)?;
+        FfroConfig::trim(r);
+        self.freq.store(freq, Ordering::Relaxed);
+        Ok(())
+    }
     fn is_enabled(&self) -> bool {
         self.state == State::Enabled
     }
 }
 
-impl ConfigurableClock for SfroConfig {
-    fn enable_and_reset(&self) -> Result<(), ClockError> {
+impl SfroConfig {
+    /// Powers up the SFRO and waits out its startup time
+    fn init_sfro() {
         // SAFETY: unsafe needed to take pointer to Sysctl0, only to power up SFRO
         let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
         sysctl0.pdruncfg0_clr().write(|w| w.sfro_pd().clr_pdruncfg0());
         // wait until ready
         while !sysctl0.pdruncfg0().read().sfro_pd().is_enabled() {}
+        // The power-down bit clears immediately, the oscillator itself still needs to start
+        settle_delay(SFRO_STARTUP_US);
+    }
+}
+
+impl ConfigurableClock for SfroConfig {
+    fn enable_and_reset(&self) -> Result<(), ClockError> {
+        SfroConfig::init_sfro();
         Ok(())
     }
     fn disable(&self) -> Result<(), ClockError> {
-        // SAFETY: unsafe needed to take pointer to Sysctl0, only to power down SFRO
-        let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
-        sysctl0.pdruncfg0_set().write(|w| w.sfro_pd().set_pdruncfg0());
-        delay_loop_clocks(30, 12_000_000);
-        // Wait until SFRO disabled
-        while !sysctl0.pdruncfg0().read().sfro_pd().is_power_down() {}
-        Ok(())
+        power_down(Clocks::Sfro)
     }
     fn get_clock_rate(&self) -> Result<u32, ClockError> {
         if self.state == State::Enabled {
@@ -688,12 +1107,14 @@ impl MultiSourceClock for MainPllClkConfig {
 
 impl ConfigurableClock for MainPllClkConfig {
     fn enable_and_reset(&self) -> Result<(), ClockError> {
-        MainPllClkConfig::init_syspll();
+        with_main_clk_off_pll(|| {
+            MainPllClkConfig::init_syspll();
 
-        MainPllClkConfig::init_syspll_pfd0(self.pfd0);
+            MainPllClkConfig::init_syspll_pfd0(self.pfd_bits(PllPfd::Pfd0));
 
-        MainPllClkConfig::init_syspll_pfd2(self.pfd2);
-        Ok(())
+            MainPllClkConfig::init_syspll_pfd2(self.pfd_bits(PllPfd::Pfd2));
+            Ok(())
+        })
     }
     fn disable(&self) -> Result<(), ClockError> {
         if self.is_enabled() {
@@ -711,60 +1132,409 @@ impl ConfigurableClock for MainPllClkConfig {
         }
     }
     fn set_clock_rate(&mut self, div: u8, mult: u8, freq: u32) -> Result<(), ClockError> {
-        if self.is_enabled() {
-            // SAFETY: unsafe needed to take pointers to Sysctl0 and Clkctl0
-            let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
-            let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
+        // Checked before the main clock is parked on FFRO / 4, not only in `program_pll`
+        check_unlocked()?;
+        // The PLL is powered down while it's reprogrammed, which would stop a CPU running from it
+        with_main_clk_off_pll(|| self.program_pll(div, mult, freq))
+    }
+    fn is_enabled(&self) -> bool {
+        self.state == State::Enabled
+    }
+}
 
-            // Power down pll before changes
-            sysctl0
-                .pdruncfg0_set()
-                .write(|w| w.syspllldo_pd().set_pdruncfg0().syspllana_pd().set_pdruncfg0());
+impl MainPllClkConfig {
+    /// Valid range for the PFD divider fields
+    const PFD_DIV_RANGE: core::ops::RangeInclusive<u8> = 12..=35;
 
-            let desired_freq: u64 = self.freq.load(Ordering::Relaxed).into();
+    /// Multipliers supported by `SYSPLL0CTL0.MULT`
+    const VALID_MULTS: [u8; 6] = [16, 17, 20, 22, 27, 33];
 
-            match self.src {
-                c if c == MainPllClkSrc::ClkIn || c == MainPllClkSrc::FFRO || c == MainPllClkSrc::SFRO => {
-                    let mut base_rate;
-                    match c {
-                        MainPllClkSrc::ClkIn => {
-                            clkctl0.syspll0clksel().write(|w| w.sel().sysxtal_clk());
-                            let r = self.get_clock_rate()?;
-                            base_rate = r;
-                        }
-                        MainPllClkSrc::FFRO => {
-                            delay_loop_clocks(1000, desired_freq);
-                            match clkctl0.ffroctl0().read().trim_range().is_ffro_48mhz() {
-                                true => base_rate = Into::into(FfroFreq::Ffro48m),
-                                false => base_rate = Into::into(FfroFreq::Ffro60m),
-                            }
-                            if div == 2 {
-                                clkctl0.syspll0clksel().write(|w| w.sel().ffro_div_2());
-                                delay_loop_clocks(150, desired_freq);
-                                base_rate /= 2;
-                            } else {
-                                return Err(ClockError::InvalidDiv);
-                            }
-                        }
-                        MainPllClkSrc::SFRO => {
-                            base_rate = SFRO_FREQ;
-                            clkctl0.syspll0clksel().write(|w| w.sel().sfro_clk());
-                        }
-                    };
-                    base_rate *= u32::from(mult);
-                    if base_rate != freq {
-                        // make sure to power syspll back up before returning the error
-                        // Clear System PLL reset
-                        clkctl0.syspll0ctl0().write(|w| w.reset().normal());
-                        // Power up SYSPLL
-                        sysctl0
-                            .pdruncfg0_clr()
-                            .write(|w| w.syspllana_pd().clr_pdruncfg0().syspllldo_pd().clr_pdruncfg0());
-                        return Err(ClockError::InvalidFrequency);
-                    }
-                    // SAFETY: unsafe needed to write the bits for the num and demon fields
-                    clkctl0.syspll0num().write(|w| unsafe { w.num().bits(0b0) });
-                    clkctl0.syspll0denom().write(|w| unsafe { w.denom().bits(0b1) });
+    /// From Section 4.6.1.1 Pll Limitations of the RT6xx User manual
+    const FREQ_RANGE: core::ops::RangeInclusive<u32> = 80_000_000..=572_000_000;
+
+    /// Checks a multiplier with fractional part `num / denom` against the PLL limits.
+    ///
+    /// The integer and fractional parts can each be legal while their sum pushes the VCO out
+    /// of range, so the check is done on the effective multiplier `mult + num / denom`.
+    /// Returns the resulting VCO frequency.
+    pub(self) fn check_fractional_mult(input_freq: u32, mult: u8, num: u32, denom: u32) -> Result<u32, ClockError> {
+        if !Self::VALID_MULTS.contains(&mult) {
+            return Err(ClockError::InvalidMult);
+        }
+        if denom == 0 || num >= denom {
+            return Err(ClockError::InvalidMult);
+        }
+        match u32::try_from(Self::fractional_vco(input_freq, mult.into(), num, denom)) {
+            Ok(vco) if Self::FREQ_RANGE.contains(&vco) => Ok(vco),
+            _ => Err(ClockError::InvalidFrequency),
+        }
+    }
+
+    /// VCO frequency for the effective multiplier `mult + num / denom`, the fraction being
+    /// ignored if `denom` is 0.
+    fn fractional_vco(input_freq: u32, mult: u32, num: u32, denom: u32) -> u64 {
+        let input = u64::from(input_freq);
+        let frac = if denom == 0 {
+            0
+        } else {
+            input * u64::from(num) / u64::from(denom)
+        };
+        input * u64::from(mult) + frac
+    }
+
+    /// VCO frequency for `input_freq` with the `MULT`, `NUM` and `DENOM` currently programmed
+    fn programmed_vco(input_freq: u32) -> u64 {
+        // SAFETY: unsafe needed to take pointer to Clkctl0, only used for register reads
+        let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+        Self::fractional_vco(
+            input_freq,
+            clkctl0.syspll0ctl0().read().mult().bits().into(),
+            clkctl0.syspll0num().read().num().bits(),
+            clkctl0.syspll0denom().read().denom().bits(),
+        )
+    }
+
+    /// Finds the multiplier and PFD divider whose output comes closest to `target_hz`.
+    ///
+    /// `input_hz` is the PLL reference rate (FFRO/2 for the default setup). Only integer
+    /// multipliers are searched. The returned [`PllPlan`] says how far off the best setting is,
+    /// so the caller can decide whether that error is acceptable before programming anything.
+    /// Errors with [`ClockError::InvalidFrequency`] if no multiplier keeps the VCO in range.
+    pub fn for_output(input_hz: u32, target_hz: u32) -> Result<PllPlan, ClockError> {
+        if target_hz == 0 {
+            return Err(ClockError::InvalidFrequency);
+        }
+        let mut best: Option<PllPlan> = None;
+        for mult in Self::VALID_MULTS {
+            let Ok(vco) = Self::check_fractional_mult(input_hz, mult, 0, 1) else {
+                continue;
+            };
+            let plan = Self::best_pfd(mult, vco, target_hz)?;
+            if best.is_none_or(|b| plan.error_hz.unsigned_abs() < b.error_hz.unsigned_abs()) {
+                best = Some(plan);
+            }
+        }
+        best.ok_or(ClockError::InvalidFrequency)
+    }
+
+    /// Plans all four PLL outputs from one VCO.
+    ///
+    /// `targets` are indexed by PFD, i.e. `main_pll_clk`, `dsp_pll_clk`, `aux0_pll_clk` and
+    /// `aux1_pll_clk`. A single integer multiplier is picked minimizing the summed error of
+    /// the requested outputs, then each gets its closest PFD divider. `None` targets come back
+    /// as `None`, meaning that PFD should stay gated. The per-output clock dividers after the
+    /// PFDs are assumed to be 1. Errors with [`ClockError::InvalidFrequency`] if nothing was
+    /// requested, a target is 0 Hz, or no multiplier keeps the VCO in range.
+    pub fn for_outputs(input_hz: u32, targets: [Option<u32>; 4]) -> Result<[Option<PllPlan>; 4], ClockError> {
+        if targets.iter().all(Option::is_none) || targets.contains(&Some(0)) {
+            return Err(ClockError::InvalidFrequency);
+        }
+        let mut best: Option<(u64, [Option<PllPlan>; 4])> = None;
+        for mult in Self::VALID_MULTS {
+            let Ok(vco) = Self::check_fractional_mult(input_hz, mult, 0, 1) else {
+                continue;
+            };
+            let mut plans = [None; 4];
+            let mut total_error = 0;
+            for (plan, target) in plans.iter_mut().zip(targets) {
+                if let Some(target_hz) = target {
+                    let p = Self::best_pfd(mult, vco, target_hz)?;
+                    total_error += u64::from(p.error_hz.unsigned_abs());
+                    *plan = Some(p);
+                }
+            }
+            if best.is_none_or(|(e, _)| total_error < e) {
+                best = Some((total_error, plans));
+            }
+        }
+        best.map(|(_, plans)| plans).ok_or(ClockError::InvalidFrequency)
+    }
+
+    /// Picks the PFD divider bringing `vco` closest to `target_hz`
+    fn best_pfd(mult: u8, vco: u32, target_hz: u32) -> Result<PllPlan, ClockError> {
+        let mut best: Option<PllPlan> = None;
+        for pfd_div in Self::PFD_DIV_RANGE {
+            let achieved_hz = Self::pfd_freq(u64::from(vco), pfd_div)?;
+            let error_hz = (i64::from(achieved_hz) - i64::from(target_hz)) as i32;
+            if best.is_none_or(|b| error_hz.unsigned_abs() < b.error_hz.unsigned_abs()) {
+                best = Some(PllPlan {
+                    mult,
+                    num: 0,
+                    denom: 1,
+                    pfd_div,
+                    achieved_hz,
+                    error_hz,
+                });
+            }
+        }
+        best.ok_or(ClockError::InvalidDiv)
+    }
+
+    /// Like [`MainPllClkConfig::for_output`], but falls back to a fractional multiplier.
+    ///
+    /// Meant for a PLL fed from clk_in at a non-round rate (e.g. a 19.2 MHz crystal), where
+    /// the integer multipliers rarely hit the target. If no integer setting is exact, the VCO
+    /// needed by each PFD divider is solved for with `denom` set to the input rate, so the
+    /// fractional part is exact to the hertz.
+    pub fn for_output_fractional(input_hz: u32, target_hz: u32) -> Result<PllPlan, ClockError> {
+        let mut best = Self::for_output(input_hz, target_hz)?;
+        if best.error_hz == 0 {
+            return Ok(best);
+        }
+        for pfd_div in Self::PFD_DIV_RANGE {
+            let vco = (u64::from(target_hz) * u64::from(pfd_div) + 9) / 18;
+            for mult in Self::VALID_MULTS {
+                let base = u64::from(input_hz) * u64::from(mult);
+                if vco < base || vco >= base + u64::from(input_hz) {
+                    continue;
+                }
+                let num = (vco - base) as u32;
+                let Ok(vco) = Self::check_fractional_mult(input_hz, mult, num, input_hz) else {
+                    continue;
+                };
+                let achieved_hz = Self::pfd_freq(u64::from(vco), pfd_div)?;
+                let error_hz = i64::from(achieved_hz) - i64::from(target_hz);
+                if error_hz.abs() < i64::from(best.error_hz).abs() {
+                    best = PllPlan {
+                        mult,
+                        num,
+                        denom: input_hz,
+                        pfd_div,
+                        achieved_hz,
+                        error_hz: error_hz as i32,
+                    };
+                }
+            }
+        }
+        Ok(best)
+    }
+
+    /// Returns the configured divider bits for `pfd`.
+    ///
+    /// This is the single place mapping each PFD to its config field, so the routing
+    /// PFD0 -> `main_pll_clk`, PFD1 -> `dsp_pll_clk`, PFD2 -> `aux0_pll_clk` and
+    /// PFD3 -> `aux1_pll_clk` can't drift between the setup code and the reported rates.
+    #[must_use]
+    pub const fn pfd_bits(&self, pfd: PllPfd) -> u8 {
+        match pfd {
+            PllPfd::Pfd0 => self.pfd0,
+            PllPfd::Pfd1 => self.pfd1,
+            PllPfd::Pfd2 => self.pfd2,
+            PllPfd::Pfd3 => self.pfd3,
+        }
+    }
+
+    /// Calculate the output of a PFD, `vco_freq * 18 / pfd_div`.
+    ///
+    /// Returns an error if `pfd_div` is outside the range the hardware accepts, or if the
+    /// result does not fit in a `u32`, instead of silently truncating.
+    pub(self) fn pfd_freq(vco_freq: u64, pfd_div: u8) -> Result<u32, ClockError> {
+        if !Self::PFD_DIV_RANGE.contains(&pfd_div) {
+            return Err(ClockError::InvalidDiv);
+        }
+        u32::try_from(vco_freq * 18 / u64::from(pfd_div)).map_err(|_| ClockError::InvalidFrequency)
+    }
+
+    /// Frequency of the clock currently selected by `SYSPLL0CLKSEL`.
+    ///
+    /// The clk_in rate is external, so it must be provided by the caller; `None` is returned
+    /// if the PLL is fed from clk_in and no rate was given, or if no input is selected.
+    pub(self) fn input_freq(clk_in_freq: Option<u32>) -> Option<u32> {
+        use pac::clkctl0::syspll0clksel::Sel as PllSel;
+        // SAFETY: unsafe needed to take pointer to Clkctl0, only used for register reads
+        let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+        match clkctl0.syspll0clksel().read().sel().variant() {
+            // 16m_irc
+            Some(PllSel::SfroClk) => Some(SFRO_FREQ),
+            // clk_in
+            Some(PllSel::SysxtalClk) => clk_in_freq,
+            // 48/60m_irc_div2
+            Some(PllSel::FfroDiv2) => Some(FfroConfig::trimmed_freq().hz() / 2),
+            _ => None,
+        }
+    }
+
+    /// Effective VCO frequency of the main PLL, `pll_input * (mult + num / denom)`.
+    ///
+    /// Each PFD output is derived from this as `vco * 18 / pfd_div`. The value is computed
+    /// from the input select, multiplier and fraction currently programmed in hardware.
+    pub fn vco_freq(&self, clk_in: &ClkInConfig) -> Result<u32, ClockError> {
+        if !self.is_enabled() {
+            return Err(ClockError::ClockNotEnabled);
+        }
+        let input = Self::input_freq(clk_in.get_clock_rate().ok()).ok_or(ClockError::ClockNotEnabled)?;
+        u32::try_from(Self::programmed_vco(input)).map_err(|_| ClockError::InvalidFrequency)
+    }
+
+    /// Programs `pfd` to `div` and ungates it at runtime, returning the resulting output rate.
+    ///
+    /// Only the selected PFD output is touched, the PLL analog block and the other PFDs keep
+    /// running. The config is updated to match.
+    pub fn enable_pfd(&mut self, pfd: PllPfd, div: u8, clk_in: &ClkInConfig) -> Result<u32, ClockError> {
+        check_unlocked()?;
+        let rate = Self::pfd_freq(u64::from(self.vco_freq(clk_in)?), div)?;
+
+        // SAFETY: unsafe needed to take pointer to Clkctl0, only to reprogram a single PFD
+        let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+        macro_rules! program_pfd {
+            ($div:ident, $gate:ident, $rdy:ident) => {{
+                clkctl0
+                    .syspll0pfd()
+                    .modify(|_, w| keep_pfd_rdy(w).$gate().set_bit());
+                // SAFETY: unsafe needed to write the PFD bits, range checked by pfd_freq above
+                clkctl0
+                    .syspll0pfd()
+                    .modify(|_, w| unsafe { keep_pfd_rdy(w).$div().bits(div) }.$gate().clear_bit());
+                while clkctl0.syspll0pfd().read().$rdy().bit_is_clear() {}
+                // clear by writing a 1
+                clkctl0
+                    .syspll0pfd()
+                    .modify(|_, w| keep_pfd_rdy(w).$rdy().set_bit());
+            }};
+        }
+        match pfd {
+            PllPfd::Pfd0 => {
+                program_pfd!(pfd0, pfd0_clkgate, pfd0_clkrdy);
+                self.pfd0 = div;
+            }
+            PllPfd::Pfd1 => {
+                program_pfd!(pfd1, pfd1_clkgate, pfd1_clkrdy);
+                self.pfd1 = div;
+            }
+            PllPfd::Pfd2 => {
+                program_pfd!(pfd2, pfd2_clkgate, pfd2_clkrdy);
+                self.pfd2 = div;
+            }
+            PllPfd::Pfd3 => {
+                program_pfd!(pfd3, pfd3_clkgate, pfd3_clkrdy);
+                self.pfd3 = div;
+            }
+        }
+        Ok(rate)
+    }
+
+    /// Gates the output of `pfd` at runtime, e.g. while its consumer sleeps.
+    ///
+    /// Only the selected PFD output is gated, the PLL analog block keeps running for the other
+    /// PFDs. Refuses with [`ClockError::ClockNotSupported`] to gate PFD0 while it feeds the main clock.
+    pub fn disable_pfd(&self, pfd: PllPfd) -> Result<(), ClockError> {
+        check_unlocked()?;
+        // SAFETY: unsafe needed to take pointer to Clkctl0, only to gate a single PFD
+        let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+        match pfd {
+            PllPfd::Pfd0 => {
+                if clkctl0.mainclkselb().read().sel().is_main_pll_clk() {
+                    return Err(ClockError::ClockNotSupported);
+                }
+                clkctl0
+                    .syspll0pfd()
+                    .modify(|_, w| keep_pfd_rdy(w).pfd0_clkgate().set_bit());
+            }
+            PllPfd::Pfd1 => {
+                clkctl0
+                    .syspll0pfd()
+                    .modify(|_, w| keep_pfd_rdy(w).pfd1_clkgate().set_bit());
+            }
+            PllPfd::Pfd2 => {
+                clkctl0
+                    .syspll0pfd()
+                    .modify(|_, w| keep_pfd_rdy(w).pfd2_clkgate().set_bit());
+            }
+            PllPfd::Pfd3 => {
+                clkctl0
+                    .syspll0pfd()
+                    .modify(|_, w| keep_pfd_rdy(w).pfd3_clkgate().set_bit());
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the live gate bit of `pfd`, which may differ from the config if the PFD was
+    /// gated with [`MainPllClkConfig::disable_pfd`] after init.
+    pub(self) fn pfd_gated(pfd: PllPfd) -> bool {
+        // SAFETY: unsafe needed to take pointer to Clkctl0, only used for register reads
+        let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+        let r = clkctl0.syspll0pfd().read();
+        match pfd {
+            PllPfd::Pfd0 => r.pfd0_clkgate().bit_is_set(),
+            PllPfd::Pfd1 => r.pfd1_clkgate().bit_is_set(),
+            PllPfd::Pfd2 => r.pfd2_clkgate().bit_is_set(),
+            PllPfd::Pfd3 => r.pfd3_clkgate().bit_is_set(),
+        }
+    }
+
+    /// Calculate the mult value of a desired frequency, return error if invalid
+    pub(self) fn calc_mult(rate: u32, base_freq: u32) -> Result<u8, ClockError> {
+        if rate > base_freq && rate.is_multiple_of(base_freq) {
+            let mult = (rate / base_freq) as u8;
+
+            if Self::VALID_MULTS.contains(&mult) {
+                Ok(mult)
+            } else {
+                Err(ClockError::InvalidFrequency)
+            }
+        } else {
+            Err(ClockError::InvalidFrequency)
+        }
+    }
+
+    /// Reprograms the PLL input and multiplier, see [`ConfigurableClock::set_clock_rate`]
+    fn program_pll(&mut self, div: u8, mult: u8, freq: u32) -> Result<(), ClockError> {
+        check_unlocked()?;
+        if self.is_enabled() {
+            // SAFETY: unsafe needed to take pointers to Sysctl0 and Clkctl0
+            let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+            let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
+
+            // Power down pll before changes
+            sysctl0
+                .pdruncfg0_set()
+                .write(|w| w.syspllldo_pd().set_pdruncfg0().syspllana_pd().set_pdruncfg0());
+
+            let desired_freq: u64 = self.freq.load(Ordering::Relaxed).into();
+
+            match self.src {
+                c if c == MainPllClkSrc::ClkIn || c == MainPllClkSrc::FFRO || c == MainPllClkSrc::SFRO => {
+                    let mut base_rate;
+                    match c {
+                        MainPllClkSrc::ClkIn => {
+                            clkctl0.syspll0clksel().write(|w| w.sel().sysxtal_clk());
+                            let r = self.get_clock_rate()?;
+                            base_rate = r;
+                        }
+                        MainPllClkSrc::FFRO => {
+                            delay_loop_clocks(1000, desired_freq);
+                            match clkctl0.ffroctl0().read().trim_range().is_ffro_48mhz() {
+                                true => base_rate = Into::into(FfroFreq::Ffro48m),
+                                false => base_rate = Into::into(FfroFreq::Ffro60m),
+                            }
+                            if div == 2 {
+                                clkctl0.syspll0clksel().write(|w| w.sel().ffro_div_2());
+                                delay_loop_clocks(150, desired_freq);
+                                base_rate /= 2;
+                            } else {
+                                return Err(ClockError::InvalidDiv);
+                            }
+                        }
+                        MainPllClkSrc::SFRO => {
+                            base_rate = SFRO_FREQ;
+                            clkctl0.syspll0clksel().write(|w| w.sel().sfro_clk());
+                        }
+                    };
+                    base_rate *= u32::from(mult);
+                    if base_rate != freq {
+                        // make sure to power syspll back up before returning the error
+                        // Clear System PLL reset
+                        clkctl0.syspll0ctl0().write(|w| w.reset().normal());
+                        // Power up SYSPLL
+                        sysctl0
+                            .pdruncfg0_clr()
+                            .write(|w| w.syspllana_pd().clr_pdruncfg0().syspllldo_pd().clr_pdruncfg0());
+                        return Err(ClockError::InvalidFrequency);
+                    }
+                    // SAFETY: unsafe needed to write the bits for the num and demon fields
+                    clkctl0.syspll0num().write(|w| unsafe { w.num().bits(0b0) });
+                    clkctl0.syspll0denom().write(|w| unsafe { w.denom().bits(0b1) });
                     delay_loop_clocks(30, desired_freq);
                     self.mult.store(mult, Ordering::Relaxed);
                     match mult {
@@ -807,18 +1577,20 @@ impl ConfigurableClock for MainPllClkConfig {
                     // SAFETY: unsafe needed to write the bits for pfd0
                     clkctl0
                         .syspll0pfd()
-                        .modify(|_, w| unsafe { w.pfd0().bits(0) }.pfd0_clkgate().gated());
+                        .modify(|_, w| unsafe { keep_pfd_rdy(w).pfd0().bits(0) }.pfd0_clkgate().gated());
                     // set pfd bits and un-gate the clock output
                     // output is multiplied by syspll * 18/pfd0_bits
                     // SAFETY: unsafe needed to write the bits for pfd0
                     clkctl0
                         .syspll0pfd()
-                        .modify(|_r, w| unsafe { w.pfd0().bits(0x12) }.pfd0_clkgate().not_gated());
+                        .modify(|_r, w| unsafe { keep_pfd_rdy(w).pfd0().bits(0x12) }.pfd0_clkgate().not_gated());
                     // wait for ready bit to be set
                     delay_loop_clocks(50, desired_freq);
                     while clkctl0.syspll0pfd().read().pfd0_clkrdy().bit_is_clear() {}
                     // clear by writing a 1
-                    clkctl0.syspll0pfd().modify(|_, w| w.pfd0_clkrdy().set_bit());
+                    clkctl0
+                        .syspll0pfd()
+                        .modify(|_, w| keep_pfd_rdy(w).pfd0_clkrdy().set_bit());
 
                     Ok(())
                 }
@@ -828,30 +1600,32 @@ impl ConfigurableClock for MainPllClkConfig {
             Err(ClockError::ClockNotEnabled)
         }
     }
-    fn is_enabled(&self) -> bool {
-        self.state == State::Enabled
-    }
-}
 
-impl MainPllClkConfig {
-    /// Calculate the mult value of a desired frequency, return error if invalid
-    pub(self) fn calc_mult(rate: u32, base_freq: u32) -> Result<u8, ClockError> {
-        const VALIDMULTS: [u8; 6] = [16, 17, 20, 22, 27, 33];
+    /// Returns whether the PLL is already powered and running with the settings `init_syspll` programs
+    fn syspll_already_configured() -> bool {
+        // SAFETY: unsafe needed to take pointers to Sysctl0 and Clkctl0, only used for register reads
+        let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+        let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
 
-        if rate > base_freq && rate.is_multiple_of(base_freq) {
-            let mult = (rate / base_freq) as u8;
+        let pdruncfg0 = sysctl0.pdruncfg0().read();
+        let ctl0 = clkctl0.syspll0ctl0().read();
 
-            if VALIDMULTS.contains(&mult) {
-                Ok(mult)
-            } else {
-                Err(ClockError::InvalidFrequency)
-            }
-        } else {
-            Err(ClockError::InvalidFrequency)
-        }
+        !pdruncfg0.syspllana_pd().bit_is_set()
+            && !pdruncfg0.syspllldo_pd().bit_is_set()
+            && ctl0.reset().bit_is_clear()
+            && ctl0.mult().bits() == 22
+            && clkctl0.syspll0clksel().read().sel().is_ffro_div_2()
+            && clkctl0.syspll0num().read().num().bits() == 0
+            && clkctl0.syspll0denom().read().denom().bits() == 1
     }
 
     pub(self) fn init_syspll() {
+        // On a warm restart the PLL may already be locked with these settings, skip the
+        // power-down and re-lock in that case. After a power-on reset it can't have survived.
+        if reset_cause() != ResetCause::PowerOn && Self::syspll_already_configured() {
+            return;
+        }
+
         // SAFETY: unsafe needed to take pointers to Sysctl0 and Clkctl0
         let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
         let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
@@ -897,19 +1671,23 @@ impl MainPllClkConfig {
         // SAFETY: unsafe needed to write the bits for pfd2
         clkctl0
             .syspll0pfd()
-            .modify(|_, w| unsafe { w.pfd2().bits(0x0) }.pfd2_clkgate().gated());
+            .modify(|_, w| unsafe { keep_pfd_rdy(w).pfd2().bits(0x0) }.pfd2_clkgate().gated());
 
         // Set the new value and enable output.
         // SAFETY: unsafe needed to write the bits for pfd2
-        clkctl0
-            .syspll0pfd()
-            .modify(|_, w| unsafe { w.pfd2().bits(config_bits) }.pfd2_clkgate().not_gated());
+        clkctl0.syspll0pfd().modify(|_, w| {
+            unsafe { keep_pfd_rdy(w).pfd2().bits(config_bits) }
+                .pfd2_clkgate()
+                .not_gated()
+        });
 
         // Wait for output becomes stable.
         while clkctl0.syspll0pfd().read().pfd2_clkrdy().bit_is_clear() {}
 
-        // Clear ready status flag.
-        clkctl0.syspll0pfd().modify(|_, w| w.pfd2_clkrdy().clear_bit());
+        // Clear ready status flag by writing a 1.
+        clkctl0
+            .syspll0pfd()
+            .modify(|_, w| keep_pfd_rdy(w).pfd2_clkrdy().set_bit());
     }
 
     /// Enables default settings for pfd0
@@ -920,21 +1698,40 @@ impl MainPllClkConfig {
         // Disable the clock output first
         clkctl0
             .syspll0pfd()
-            .modify(|_, w| unsafe { w.pfd0().bits(0) }.pfd0_clkgate().gated());
+            .modify(|_, w| unsafe { keep_pfd_rdy(w).pfd0().bits(0) }.pfd0_clkgate().gated());
 
         // Set the new value and enable output
-        clkctl0
-            .syspll0pfd()
-            .modify(|_, w| unsafe { w.pfd0().bits(config_bits) }.pfd0_clkgate().not_gated());
+        clkctl0.syspll0pfd().modify(|_, w| {
+            unsafe { keep_pfd_rdy(w).pfd0().bits(config_bits) }
+                .pfd0_clkgate()
+                .not_gated()
+        });
 
         // Wait for output becomes stable
         while clkctl0.syspll0pfd().read().pfd0_clkrdy().bit_is_clear() {}
 
-        // Clear ready status flag
-        clkctl0.syspll0pfd().modify(|_, w| w.pfd0_clkrdy().clear_bit());
+        // Clear ready status flag by writing a 1
+        clkctl0
+            .syspll0pfd()
+            .modify(|_, w| keep_pfd_rdy(w).pfd0_clkrdy().set_bit());
     }
 }
 
+/// Zeroes the write-1-to-clear `PFDn_CLKRDY` flags in a `SYSPLL0PFD` write.
+///
+/// `modify` writes back what it read, so updating one PFD would otherwise also clear any ready
+/// flag another PFD has set. Callers set the one flag they do mean to clear after this.
+fn keep_pfd_rdy(w: &mut pac::clkctl0::syspll0pfd::W) -> &mut pac::clkctl0::syspll0pfd::W {
+    w.pfd0_clkrdy()
+        .clear_bit()
+        .pfd1_clkrdy()
+        .clear_bit()
+        .pfd2_clkrdy()
+        .clear_bit()
+        .pfd3_clkrdy()
+        .clear_bit()
+}
+
 impl MainClkConfig {
     /// Configure the FFRO/4 as the main clock source.
     ///
@@ -945,71 +1742,91 @@ impl MainClkConfig {
         clkctl0.mainclkselb().write(|w| w.sel().main_1st_clk());
     }
 
-    fn init_main_clk(&self) {
+    fn init_main_clk(&self) -> Result<(), ClockError> {
+        check_unlocked()?;
         // SAFETY:: unsafe needed to take pointers to Clkctl0 and Clkctl1
         // used to set the right HW frequency
         let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
         let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
 
-        let (clk_a, clk_b) = {
-            use pac::clkctl0::mainclksela::Sel as SelA;
-            use pac::clkctl0::mainclkselb::Sel as SelB;
-            match self.src {
-                MainClkSrc::FFROdiv4 => (Some(SelA::FfroDiv4), SelB::Main1stClk),
-                MainClkSrc::ClkIn => (Some(SelA::SysxtalClk), SelB::Main1stClk),
-                MainClkSrc::Lposc => (Some(SelA::Lposc), SelB::Main1stClk),
-                MainClkSrc::FFRO => (Some(SelA::FfroClk), SelB::Main1stClk),
-                MainClkSrc::SFRO => (None, SelB::SfroClk),
-                MainClkSrc::PllMain => (None, SelB::MainPllClk),
-                MainClkSrc::RTC32k => (None, SelB::Rtc32kClk),
-            }
-        };
+        let (clk_a, clk_b) = self.src.mux_sel();
 
         if let Some(clk_a) = clk_a {
             clkctl0.mainclksela().write(|w| w.sel().variant(clk_a));
         }
         clkctl0.mainclkselb().write(|w| w.sel().variant(clk_b));
 
-        // Set PFC0DIV divider to value 2, Subtract 1 since 0-> 1, 1-> 2, etc...
-        clkctl0.pfcdiv(0).modify(|_, w| w.reset().set_bit());
-        // SAFETY: unsafe needed to write the bits for pfcdiv
-        clkctl0
-            .pfcdiv(0)
-            .write(|w| unsafe { w.div().bits(2 - 1) }.halt().clear_bit());
-        while clkctl0.pfcdiv(0).read().reqflag().bit_is_set() {}
+        // Set PFC0DIV divider to value 2
+        set_trace_clk_div(Divider(2))?;
+
+        // Set FRGPLLCLKDIV divider to value 12
+        clkctl1.frgpllclkdiv().set_div(Divider(12));
+        Ok(())
+    }
 
-        // Set FRGPLLCLKDIV divider to value 12, Subtract 1 since 0-> 1, 1-> 2, etc...
-        clkctl1.frgpllclkdiv().modify(|_, w| w.reset().set_bit());
-        // SAFETY: unsafe needed to write the bits for frgpllclkdiv
-        clkctl1
-            .frgpllclkdiv()
-            .write(|w| unsafe { w.div().bits(12 - 1) }.halt().clear_bit());
-        while clkctl1.frgpllclkdiv().read().reqflag().bit_is_set() {}
+    /// Returns `div_int` as a [`Divider`], erroring if it is out of range
+    fn ahb_div(&self) -> Result<Divider, ClockError> {
+        u16::try_from(self.div_int.load(Ordering::Relaxed))
+            .map_err(|_| ClockError::InvalidDiv)
+            .and_then(Divider::new)
+    }
+
+    /// Returns the main clock rate, making sure it is actually usable.
+    ///
+    /// Errors if the main clock is disabled or resolves to 0 Hz, so a misconfigured main clock
+    /// surfaces as an error instead of propagating a zero frequency into rate calculations.
+    pub fn ensure_main_clk(&self) -> Result<u32, ClockError> {
+        let (_c, rate) = self.get_clock_source_and_rate(&Clocks::MainClk)?;
+        if rate == 0 {
+            Err(ClockError::InvalidFrequency)
+        } else {
+            Ok(rate)
+        }
+    }
+}
+/// Runs `f` with the main clock moved off the main PLL while it's reconfigured.
+///
+/// If `MAINCLKSELB` selects `main_pll_clk`, e.g. after a warm reset that kept the clock tree,
+/// powering the PLL down would stop the CPU and hang. The main clock is parked on FFRO / 4, the
+/// reset default, for the duration of `f`, then switched back to the PLL. The AHB divider is
+/// left alone, so it must already suit the PLL rate `f` leaves behind. The FFRO is powered up
+/// first if needed, erroring as [`ensure_running`] does if it can't be.
+fn with_main_clk_off_pll<R>(f: impl FnOnce() -> Result<R, ClockError>) -> Result<R, ClockError> {
+    // SAFETY: unsafe needed to take pointer to Clkctl0, only to move the main clock mux
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+    let on_pll = clkctl0.mainclkselb().read().sel().is_main_pll_clk();
+    if on_pll {
+        ensure_running(Clocks::Ffro)?;
+        MainClkConfig::reset_main_clk();
+    }
+    let result = f();
+    if on_pll {
+        clkctl0.mainclkselb().write(|w| w.sel().main_pll_clk());
     }
+    result
 }
+
 impl MultiSourceClock for MainClkConfig {
     fn get_clock_source_and_rate(&self, clock: &Clocks) -> Result<(Clocks, u32), ClockError> {
         match clock {
             Clocks::MainClk => {
                 let div: u32 = if self.src == MainClkSrc::FFROdiv4 { 4 } else { 1 };
                 let converted_clock = Clocks::from(self.src);
-                match ConfigurableClock::get_clock_rate(self) {
-                    Ok(_rate) => {
-                        // SAFETY: unsafe needed to take pointer to Clkctl0
-                        // needed to calculate the clock rate from the bits written in the registers
-                        let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
-                        if self.src == MainClkSrc::PllMain && clkctl0.syspll0ctl0().read().bypass().is_programmed_clk()
-                        {
-                            let mut temp;
-                            temp = self.freq.load(Ordering::Relaxed)
-                                * u32::from(clkctl0.syspll0ctl0().read().mult().bits());
-                            temp = (u64::from(temp) * 18 / u64::from(clkctl0.syspll0pfd().read().pfd0().bits())) as u32;
-                            return Ok((converted_clock, temp));
-                        }
-                        Ok((converted_clock, self.freq.load(Ordering::Relaxed) / div))
+                if !self.is_enabled() {
+                    return Err(ClockError::ClockNotEnabled);
+                }
+                // SAFETY: unsafe needed to take pointer to Clkctl0
+                // needed to calculate the clock rate from the bits written in the registers
+                let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+                if self.src == MainClkSrc::PllMain && clkctl0.syspll0ctl0().read().bypass().is_programmed_clk() {
+                    // The clk_in rate isn't known here, keep the stored rate if the PLL is fed from it
+                    if let Some(input) = MainPllClkConfig::input_freq(None) {
+                        let vco = MainPllClkConfig::programmed_vco(input);
+                        let rate = MainPllClkConfig::pfd_freq(vco, clkctl0.syspll0pfd().read().pfd0().bits())?;
+                        return Ok((converted_clock, rate));
                     }
-                    Err(clk_err) => Err(clk_err),
                 }
+                Ok((converted_clock, self.freq.load(Ordering::Relaxed) / div))
             }
             _ => Err(ClockError::ClockMismatch),
         }
@@ -1020,16 +1837,23 @@ impl MultiSourceClock for MainClkConfig {
         clock_src: &Clocks,
         rate: u32,
     ) -> Result<(), ClockError> {
+        check_unlocked()?;
         if !clock_src_config.is_enabled() {
             return Err(ClockError::ClockNotEnabled);
         }
 
-        let c = <Clocks as TryInto<MainClkSrc>>::try_into(*clock_src).map_err(|_| ClockError::ClockNotSupported)?;
+        let c = <Clocks as TryInto<MainClkSrc>>::try_into(*clock_src).map_err(|_| {
+            error!(
+                "{:?} can't feed the main clock, of the PLL outputs only main_pll_clk (PFD0) can",
+                clock_src
+            );
+            ClockError::ClockNotSupported
+        })?;
 
         // SAFETY: unsafe needed to take pointer to Clkctl0
         // needed to change the clock source
         let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
-        match c {
+        let result = match c {
             MainClkSrc::ClkIn => {
                 self.src = MainClkSrc::ClkIn;
 
@@ -1084,10 +1908,7 @@ impl MultiSourceClock for MainClkConfig {
             }
             MainClkSrc::PllMain => {
                 let r = rate;
-                // From Section 4.6.1.1 Pll Limitations of the RT6xx User manual
-                let pll_max = 572_000_000;
-                let pll_min = 80_000_000;
-                if pll_min <= r && r <= pll_max {
+                if MainPllClkConfig::FREQ_RANGE.contains(&r) {
                     clkctl0.mainclkselb().write(|w| w.sel().main_pll_clk());
                     self.src = MainClkSrc::PllMain;
                     self.freq.store(r, Ordering::Relaxed);
@@ -1097,6 +1918,7 @@ impl MultiSourceClock for MainClkConfig {
                 }
             }
             MainClkSrc::RTC32k => {
+                check_32k_main_clk()?;
                 if rate == RtcFreq::SubSecond32kHz as u32 {
                     self.src = MainClkSrc::RTC32k;
                     self.freq.store(rate, Ordering::Relaxed);
@@ -1106,21 +1928,42 @@ impl MultiSourceClock for MainClkConfig {
                     Err(ClockError::InvalidFrequency)
                 }
             }
+        };
+
+        if result.is_ok() {
+            update_clock_cache(self.freq.load(Ordering::Relaxed));
         }
+        result
+    }
+}
+
+/// Checks that the main clock can drop to 32 kHz, see [`MainClkSrc::RTC32k`].
+///
+/// Errors with [`ClockError::ClockInUse`] if FlexSPI is clocked from the main clock, and warns
+/// if the ADC is enabled since its function clock can't be met from a 32 kHz main clock.
+fn check_32k_main_clk() -> Result<(), ClockError> {
+    // SAFETY: unsafe needed to take pointer to Clkctl0, only used for register reads
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+    if clkctl0.flexspifclksel().read().sel().is_main_clk() {
+        error!("main clock to 32k_clk refused: FlexSPI is clocked from the main clock");
+        return Err(ClockError::ClockInUse);
     }
+    if <crate::peripherals::ADC0 as SealedSysconPeripheral>::is_perph_clock_enabled() {
+        warn!("main clock switched to 32k_clk with the ADC enabled");
+    }
+    Ok(())
 }
 
 impl ConfigurableClock for MainClkConfig {
     fn enable_and_reset(&self) -> Result<(), ClockError> {
-        self.init_main_clk();
+        self.init_main_clk()?;
         Ok(())
     }
     fn disable(&self) -> Result<(), ClockError> {
         Err(ClockError::ClockNotSupported)
     }
     fn get_clock_rate(&self) -> Result<u32, ClockError> {
-        let (_c, rate) = MainClkConfig::get_clock_source_and_rate(self, &Clocks::MainClk)?;
-        Ok(rate)
+        self.ensure_main_clk()
     }
     fn set_clock_rate(&mut self, _div: u8, _mult: u8, _freq: u32) -> Result<(), ClockError> {
         Err(ClockError::ClockNotSupported)
@@ -1139,21 +1982,61 @@ impl ConfigurableClock for ClkInConfig {
         Err(ClockError::ClockNotSupported)
     }
     fn get_clock_rate(&self) -> Result<u32, ClockError> {
-        if self.freq.is_some() {
-            Ok(self.freq.as_ref().unwrap().load(Ordering::Relaxed))
-        } else {
-            Err(ClockError::ClockNotEnabled)
+        match &self.freq {
+            Some(freq) => Ok(freq.load(Ordering::Relaxed)),
+            None => Err(ClockError::ClockNotEnabled),
         }
     }
     fn set_clock_rate(&mut self, _div: u8, _mult: u8, freq: u32) -> Result<(), ClockError> {
-        self.freq.as_ref().unwrap().store(freq, Ordering::Relaxed);
-        Ok(())
+        match &self.freq {
+            Some(f) => {
+                f.store(freq, Ordering::Relaxed);
+                Ok(())
+            }
+            None => Err(ClockError::ClockNotEnabled),
+        }
     }
     fn is_enabled(&self) -> bool {
         self.state == State::Enabled
     }
 }
 
+impl ClkInConfig {
+    /// Reference frequency the USB PHY PLL locks to
+    const USB_PHY_REF_FREQ: u32 = 24_000_000;
+
+    /// Returns the configured clk_in rate, or `None` if there is no clk_in.
+    ///
+    /// clk_in is external, so this is the rate given in the config and can't be checked against
+    /// hardware. Consumers that can select clk_in (the main PLL, the main clock, the USB PHY)
+    /// should use it to validate their own settings.
+    #[must_use]
+    pub fn clk_in_hz(&self) -> Option<u32> {
+        if !self.is_enabled() {
+            return None;
+        }
+        self.freq
+            .as_ref()
+            .map(|freq| freq.load(Ordering::Relaxed))
+            .filter(|&hz| hz != 0)
+    }
+
+    /// Checks that clk_in can serve as the USB PHY PLL reference, returning its rate.
+    ///
+    /// The PHY derives its 480 MHz PLL from a 24 MHz reference and won't enumerate with any
+    /// other, so this errors with [`ClockError::InvalidFrequency`] for any other rate instead
+    /// of leaving it to show up as a silent enumeration failure.
+    pub fn check_usb_phy_ref(&self) -> Result<u32, ClockError> {
+        if !self.is_enabled() {
+            return Err(ClockError::ClockNotEnabled);
+        }
+        match self.get_clock_rate()? {
+            Self::USB_PHY_REF_FREQ => Ok(Self::USB_PHY_REF_FREQ),
+            _ => Err(ClockError::InvalidFrequency),
+        }
+    }
+}
+
 impl RtcClkConfig {
     /// Register writes to initialize the RTC Clock
     fn init_rtc_clk() {
@@ -1173,7 +2056,10 @@ impl RtcClkConfig {
         // SAFETY: unsafe needed to write the bits
         r.wake().write(|w| unsafe { w.bits(0xA) });
 
-        // Enable 32K OSC
+        // Enable 32K OSC, it needs its startup time again if it was off
+        if !cc0.osc32khzctl0().read().ena32khz().bit_is_set() {
+            OSC32K_STABLE.store(false, Ordering::Relaxed);
+        }
         cc0.osc32khzctl0().write(|w| w.ena32khz().enabled());
 
         // enable rtc clk
@@ -1181,16 +2067,131 @@ impl RtcClkConfig {
     }
 }
 
-impl ConfigurableClock for RtcClkConfig {
-    fn enable_and_reset(&self) -> Result<(), ClockError> {
-        // should only be called once if previously disabled
-        RtcClkConfig::init_rtc_clk();
-        Ok(())
+/// Conservative startup time of the 32.768 kHz crystal oscillator
+const OSC32K_STARTUP_US: u64 = 500_000;
+
+/// Set once the 32 kHz oscillator has been given [`OSC32K_STARTUP_US`] since it was enabled
+static OSC32K_STABLE: AtomicBool = AtomicBool::new(false);
+
+/// Returns whether the 32 kHz crystal oscillator is enabled and past its startup time.
+///
+/// The oscillator has no ready flag, so it is only reported stable once [`wait_32k_stable`]
+/// has waited out its startup time. Consumers such as the RTC should not rely on the 32 kHz
+/// timebase before this returns `true`.
+#[must_use]
+pub fn is_32k_stable() -> bool {
+    is_clock_running(Clocks::Rtc) && OSC32K_STABLE.load(Ordering::Relaxed)
+}
+
+/// Waits for the 32 kHz crystal oscillator to finish starting up.
+///
+/// A 32.768 kHz crystal takes hundreds of milliseconds to start, and with no ready flag in
+/// hardware this busy-waits a conservative startup delay timed from the main clock. Returns
+/// immediately if the oscillator is already known to be stable.
+pub fn wait_32k_stable(main_clk: &MainClkConfig) -> Result<(), ClockError> {
+    if !is_clock_running(Clocks::Rtc) {
+        return Err(ClockError::ClockNotRunning);
     }
-    fn disable(&self) -> Result<(), ClockError> {
-        Err(ClockError::ClockNotSupported)
+    if !OSC32K_STABLE.load(Ordering::Relaxed) {
+        delay_loop_clocks(OSC32K_STARTUP_US, u64::from(main_clk.ensure_main_clk()?));
+        OSC32K_STABLE.store(true, Ordering::Relaxed);
     }
-    fn set_clock_rate(&mut self, _div: u8, _mult: u8, freq: u32) -> Result<(), ClockError> {
+    Ok(())
+}
+
+/// Returns the rate of the 32 kHz wake clock, or `None` if its selected source isn't running.
+///
+/// The wake clock is muxed by `WAKECLK32KHZSEL` from either the 32.768 kHz crystal oscillator
+/// or `lp_32k` (1m_lposc / 32, 31.25 kHz). Low-power time sources ticking from it should use
+/// this rather than assuming 32.768 kHz.
+#[must_use]
+pub fn wake_clk_hz() -> Option<u32> {
+    use pac::clkctl0::wakeclk32khzsel::Sel as WakeSel;
+    // SAFETY: unsafe needed to take pointer to Clkctl0, only used for a register read
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+    match clkctl0.wakeclk32khzsel().read().sel().variant() {
+        Some(WakeSel::Freq32khz) => is_clock_running(Clocks::Rtc).then_some(RtcFreq::SubSecond32kHz.hz()),
+        Some(WakeSel::Lposc) => is_clock_running(Clocks::Lposc).then_some(LposcFreq::Lp32k.hz()),
+        _ => None,
+    }
+}
+
+/// 32 kHz wake clock source, selected by `WAKECLK32KHZSEL`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WakeClkSrc {
+    /// 32.768 kHz crystal oscillator, accurate
+    Osc32k,
+    /// `lp_32k` (1m_lposc / 32, 31.25 kHz), lower power but less accurate
+    Lp32k,
+}
+
+/// Switches the 32 kHz wake clock to `src` at runtime, returning its new rate.
+///
+/// Trades accuracy (crystal) for power (`lp_32k`) after init. The LPOSC is powered on demand
+/// for `lp_32k`, the crystal has to be running already since its startup takes hundreds of
+/// milliseconds. Refuses with [`ClockError::ClockInUse`] while the OS event timer counts the
+/// wake clock, since its tick rate would change under scheduled wakeups.
+pub fn set_wake_clk_source(src: WakeClkSrc) -> Result<Option<u32>, ClockError> {
+    use pac::clkctl0::wakeclk32khzsel::Sel as WakeSel;
+    check_unlocked()?;
+    // SAFETY: unsafe needed to take pointers to Clkctl0 and Clkctl1, only to switch the wake clock mux
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+
+    if clkctl1.oseventfclksel().read().sel().is_rtc_32khz_clk() {
+        return Err(ClockError::ClockInUse);
+    }
+    let sel = match src {
+        WakeClkSrc::Osc32k if !is_clock_running(Clocks::Rtc) => return Err(ClockError::ClockNotRunning),
+        WakeClkSrc::Osc32k => WakeSel::Freq32khz,
+        WakeClkSrc::Lp32k => {
+            ensure_running(Clocks::Lposc)?;
+            WakeSel::Lposc
+        }
+    };
+    clkctl0.wakeclk32khzsel().write(|w| w.sel().variant(sel));
+    Ok(wake_clk_hz())
+}
+
+impl RtcClkConfig {
+    /// Returns whether the RTC output running at `freq` is currently enabled in hardware.
+    ///
+    /// The 1 Hz output drives the calendar counter, the 1 kHz output drives the wake timer.
+    #[must_use]
+    pub fn is_output_enabled(&self, freq: RtcFreq) -> bool {
+        // SAFETY: unsafe needed to take pointer to RTC, only used for a register read
+        let rtc = unsafe { crate::pac::Rtc::steal() };
+        let ctrl = rtc.ctrl().read();
+        match freq {
+            RtcFreq::Default1Hz => ctrl.rtc_en().is_enable(),
+            RtcFreq::HighResolution1khz => ctrl.rtc1khz_en().is_enable(),
+            RtcFreq::SubSecond32kHz => ctrl.rtc_subsec_ena().is_enable(),
+        }
+    }
+}
+
+impl ConfigurableClock for RtcClkConfig {
+    fn enable_and_reset(&self) -> Result<(), ClockError> {
+        // should only be called once if previously disabled
+        RtcClkConfig::init_rtc_clk();
+
+        // The 1 Hz output is always enabled by init, bring up the other outputs if requested
+        // SAFETY: unsafe needed to take pointer to RTC, needed to enable the RTC outputs
+        let rtc = unsafe { crate::pac::Rtc::steal() };
+        if self.wake_alarm_state == State::Enabled {
+            rtc.ctrl().modify(|_r, w| w.rtc1khz_en().enable());
+        }
+        if self.sub_second_state == State::Enabled {
+            rtc.ctrl().modify(|_r, w| w.rtc_subsec_ena().enable());
+        }
+        Ok(())
+    }
+    fn disable(&self) -> Result<(), ClockError> {
+        Err(ClockError::ClockNotSupported)
+    }
+    fn set_clock_rate(&mut self, _div: u8, _mult: u8, freq: u32) -> Result<(), ClockError> {
+        check_unlocked()?;
         if let Ok(r) = <u32 as TryInto<RtcFreq>>::try_into(freq) {
             // SAFETY: unsafe needed to take pointer to RTC
             // needed to enable the HW for the different RTC frequencies, powered down by default
@@ -1241,7 +2242,9 @@ impl ConfigurableClock for RtcClkConfig {
 
 impl SysClkConfig {
     /// Updates the system core clock frequency, SW concept used for systick
-    fn update_sys_core_clock(&self) {}
+    fn update_sys_core_clock(&self, hclk_freq: u32) {
+        self.sysclkfreq.store(hclk_freq, Ordering::Relaxed);
+    }
 }
 
 impl ConfigurableClock for SysOscConfig {
@@ -1297,6 +2300,67 @@ impl ConfigurableClock for SysOscConfig {
     }
 }
 
+/// Human readable rendering of a frequency, for logging.
+///
+/// Renders MHz with two decimals (`250.00 MHz`), kHz with three (`32.768 kHz`), and plain Hz
+/// below that, without allocating. Works with both `core::fmt` and defmt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FreqDisplay(pub u32);
+
+impl FreqDisplay {
+    /// Splits the frequency into its whole part, fraction, fraction width and unit
+    const fn parts(self) -> (u32, u32, usize, &'static str) {
+        let hz = self.0;
+        if hz >= 1_000_000 {
+            // round to the nearest 10 kHz
+            let hundredths = (hz as u64 + 5_000) / 10_000;
+            ((hundredths / 100) as u32, (hundredths % 100) as u32, 2, "MHz")
+        } else if hz >= 1_000 {
+            (hz / 1_000, hz % 1_000, 3, "kHz")
+        } else {
+            (hz, 0, 0, "Hz")
+        }
+    }
+}
+
+impl core::fmt::Display for FreqDisplay {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.parts() {
+            (whole, _, 0, unit) => write!(f, "{whole} {unit}"),
+            (whole, frac, width, unit) => write!(f, "{whole}.{frac:0width$} {unit}"),
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for FreqDisplay {
+    fn format(&self, fmt: defmt::Formatter) {
+        match self.parts() {
+            (whole, _, 0, unit) => defmt::write!(fmt, "{=u32} {=str}", whole, unit),
+            (whole, frac, 2, unit) => defmt::write!(fmt, "{=u32}.{=u32:02} {=str}", whole, frac, unit),
+            (whole, frac, _, unit) => defmt::write!(fmt, "{=u32}.{=u32:03} {=str}", whole, frac, unit),
+        }
+    }
+}
+
+/// Conservative startup time of the 16 MHz SFRO after clearing its power-down bit
+const SFRO_STARTUP_US: u64 = 20;
+
+/// Conservative startup time of the 48/60 MHz FFRO after clearing its power-down bit
+const FFRO_STARTUP_US: u64 = 50;
+
+/// Busy-waits `usec` for an oscillator to settle after power-up.
+///
+/// Times the wait from the cached main clock rate. Before init, when it isn't known yet, the
+/// boot main clock is assumed to be FFRO / 4 at the faster 60 MHz trim, so the wait errs long.
+fn settle_delay(usec: u64) {
+    let main_clk = match main_clk_hz() {
+        0 => FfroFreq::Ffro60m.hz() / 4,
+        hz => hz,
+    };
+    delay_loop_clocks(usec, u64::from(main_clk));
+}
+
 /// Method to delay for a certain number of microseconds given a clock rate
 ///
 /// Given `usec` and `freq_hz`, this method will compute the number of
@@ -1322,6 +2386,224 @@ pub fn delay_loop_clocks(usec: u64, freq_hz: u64) {
     cortex_m::asm::delay(ticks as u32);
 }
 
+/// Returns whether the hardware backing `clock` is currently running.
+///
+/// Unlike [`ConfigurableClock::is_enabled`], which reports the state requested in the
+/// [`ClockConfig`], this reads the live power-down and gate bits, so it reflects
+/// sources that have been powered down or gated since init.
+#[must_use]
+pub fn is_clock_running(clock: Clocks) -> bool {
+    // SAFETY: unsafe needed to take pointers to Sysctl0 and Clkctl0, only used for register reads
+    let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+
+    match clock {
+        Clocks::Lposc => !sysctl0.pdruncfg0().read().lposc_pd().is_power_down(),
+        Clocks::Sfro => !sysctl0.pdruncfg0().read().sfro_pd().is_power_down(),
+        Clocks::Ffro => !sysctl0.pdruncfg0().read().ffro_pd().is_power_down(),
+        Clocks::SysOscClk => !sysctl0.pdruncfg0().read().sysxtal_pd().is_power_down(),
+        Clocks::Rtc => clkctl0.osc32khzctl0().read().ena32khz().bit_is_set(),
+        Clocks::MainPllClk => {
+            !sysctl0.pdruncfg0().read().syspllana_pd().bit_is_set()
+                && !clkctl0.syspll0pfd().read().pfd0_clkgate().bit_is_set()
+        }
+        // These are either derived from the main clock, which is always running while code
+        // executes, or are external and cannot be observed.
+        Clocks::ClkIn | Clocks::Hclk | Clocks::MainClk | Clocks::SysClk | Clocks::Adc => true,
+    }
+}
+
+/// Powers up `clock` on demand if it isn't running yet.
+///
+/// Lets a driver bring up the oscillator its function clock needs instead of failing when it
+/// wasn't enabled at init. Only the internal oscillators can be started this way, other clocks
+/// error with [`ClockError::ClockNotSupported`] unless they are already running. Powering up
+/// on behalf of a peripheral that is being enabled is allowed after [`lock_clocks`].
+pub fn ensure_running(clock: Clocks) -> Result<(), ClockError> {
+    critical_section::with(|_| {
+        if is_clock_running(clock) {
+            return Ok(());
+        }
+        match clock {
+            Clocks::Lposc => {
+                LposcConfig::init_lposc();
+                Ok(())
+            }
+            Clocks::Sfro => {
+                SfroConfig::init_sfro();
+                Ok(())
+            }
+            Clocks::Ffro => {
+                FfroConfig::init_ffro_clk();
+                Ok(())
+            }
+            _ => Err(ClockError::ClockNotSupported),
+        }
+    })
+}
+
+/// Returns whether an active clock currently depends on the oscillator `clock`.
+///
+/// Checks the main clock mux, the main PLL input while the PLL runs, the 32 kHz wake clock
+/// (`lp_32k`), and every function clock mux that can select an oscillator directly: the
+/// flexcomms and their FRGs, the CTimers, SysTick, CLKOUT, the ADC, ACMP, DMIC and I3C,
+/// FlexSPI, SCT and both SDIOs (FFRO), and the OS event timer, UTICK and both watchdogs
+/// (LPOSC). The OS event timer and watchdog muxes reset to LPOSC, so they only count while
+/// their peripheral clock is enabled.
+fn oscillator_in_use(clock: Clocks) -> bool {
+    // SAFETY: unsafe needed to take pointers to Clkctl0 and Clkctl1, only used for register reads
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+
+    let sela = clkctl0.mainclksela().read().sel();
+    let selb = clkctl0.mainclkselb().read().sel();
+    let main_1st = selb.is_main_1st_clk();
+    let pll_input = is_clock_running(Clocks::MainPllClk).then(|| clkctl0.syspll0clksel().read().sel());
+    let systick_sel = clkctl0.systickfclksel().read().sel();
+    let clkout = ClockOutConfig::current_source();
+    let adc_sel = clkctl0
+        .adc0fclksel1()
+        .read()
+        .sel()
+        .is_adc0fclksel0_mux_out()
+        .then(|| clkctl0.adc0fclksel0().read().sel());
+    let acmp_sel = clkctl1.acmp0fclksel().read().sel();
+    let dmic_sel = clkctl1.dmic0fclksel().read().sel();
+
+    match clock {
+        Clocks::Lposc => {
+            (main_1st && sela.is_lposc())
+                || (clkctl1.oseventfclksel().read().sel().is_lposc()
+                    && <crate::peripherals::OS_EVENT as SealedSysconPeripheral>::is_perph_clock_enabled())
+                || (clkctl0.wdt0fclksel().read().sel().is_lposc()
+                    && <crate::peripherals::WDT0 as SealedSysconPeripheral>::is_perph_clock_enabled())
+                || (clkctl1.wdt1fclksel().read().sel().is_lposc()
+                    && <crate::peripherals::WDT1 as SealedSysconPeripheral>::is_perph_clock_enabled())
+                || clkctl0.wakeclk32khzsel().read().sel().is_lposc()
+                || (0..CTIMER_COUNT).any(|i| clkctl1.ct32bitfclksel(i).read().sel().is_lposc())
+                || systick_sel.is_lposc()
+                || clkout == ClkOutSrc::Lposc
+                || adc_sel.as_ref().is_some_and(|sel| sel.is_lposc())
+                || dmic_sel.is_lposc()
+                || clkctl1.i3c0fclkstcsel().read().sel().is_lposc()
+                || clkctl0.utickfclksel().read().sel().is_lposc()
+        }
+        // 16m_irc
+        Clocks::Sfro => {
+            selb.is_sfro_clk()
+                || pll_input.as_ref().is_some_and(|sel| sel.is_sfro_clk())
+                || flexcomm_fed_from_fro(false)
+                || (0..CTIMER_COUNT).any(|i| clkctl1.ct32bitfclksel(i).read().sel().is_sfro_clk())
+                || systick_sel.is_sfro_clk()
+                || clkout == ClkOutSrc::Sfro
+                || adc_sel.as_ref().is_some_and(|sel| sel.is_sfro_clk())
+                || acmp_sel.is_sfro_clk()
+                || dmic_sel.is_sfro_clk()
+        }
+        // 48/60m_irc_div2
+        Clocks::Ffro => {
+            (main_1st && (sela.is_ffro_clk() || sela.is_ffro_div_4()))
+                || pll_input.as_ref().is_some_and(|sel| sel.is_ffro_div_2())
+                || clkctl0.flexspifclksel().read().sel().is_ffro_clk()
+                || flexcomm_fed_from_fro(true)
+                || (0..CTIMER_COUNT).any(|i| clkctl1.ct32bitfclksel(i).read().sel().is_ffro_clk())
+                || clkout == ClkOutSrc::Ffro
+                || adc_sel.as_ref().is_some_and(|sel| sel.is_ffro_clk())
+                || acmp_sel.is_ffro_clk()
+                || dmic_sel.is_ffro_clk()
+                || clkctl1.i3c0fclksel().read().sel().is_ffro_clk()
+                || clkctl0.sctfclksel().read().sel().is_ffro_clk()
+                || clkctl0.sdio0fclksel().read().sel().is_ffro_clk()
+                || clkctl0.sdio1fclksel().read().sel().is_ffro_clk()
+        }
+        _ => false,
+    }
+}
+
+/// Number of CTimer function clock muxes, `CT32BITFCLKSEL0` to `CT32BITFCLKSEL4`
+const CTIMER_COUNT: usize = 5;
+
+/// Returns whether a flexcomm function clock, or the FRG behind it, selects the FFRO (`ffro`)
+/// or the SFRO. Disabled flexcomms have both muxes set to none.
+fn flexcomm_fed_from_fro(ffro: bool) -> bool {
+    // SAFETY: unsafe needed to take pointer to Clkctl1, only used for register reads
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+
+    macro_rules! fed_from_fro {
+        ($fclksel:expr, $frgclksel:expr) => {{
+            let fclk = $fclksel.read().sel();
+            let frg = $frgclksel.read().sel();
+            if ffro {
+                fclk.is_ffro_clk() || (fclk.is_fcn_frg_clk() && frg.is_ffro_clk())
+            } else {
+                fclk.is_sfro_clk() || (fclk.is_fcn_frg_clk() && frg.is_sfro_clk())
+            }
+        }};
+    }
+
+    (0..8).any(|i| fed_from_fro!(clkctl1.flexcomm(i).fcfclksel(), clkctl1.flexcomm(i).frgclksel()))
+        || fed_from_fro!(clkctl1.fc14fclksel(), clkctl1.frg14clksel())
+        || fed_from_fro!(clkctl1.fc15fclksel(), clkctl1.frg15clksel())
+}
+
+/// Powers down the oscillator `clock`, refusing if an active clock still depends on it.
+///
+/// Counterpart to [`ensure_running`] for fine-grained power control after init. Errors with
+/// [`ClockError::ClockInUse`] if e.g. the main clock or the running main PLL is fed from
+/// `clock`, and with [`ClockError::ClockNotSupported`] for anything but the internal
+/// oscillators.
+pub fn power_down(clock: Clocks) -> Result<(), ClockError> {
+    critical_section::with(|_| {
+        check_unlocked()?;
+        if oscillator_in_use(clock) {
+            return Err(ClockError::ClockInUse);
+        }
+        // SAFETY: unsafe needed to take pointer to Sysctl0, only to power down `clock`
+        let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
+        match clock {
+            Clocks::Lposc => {
+                sysctl0.pdruncfg0_set().write(|w| w.lposc_pd().set_pdruncfg0());
+                // Wait until LPOSC disabled
+                while !sysctl0.pdruncfg0().read().lposc_pd().is_power_down() {}
+            }
+            Clocks::Sfro => {
+                sysctl0.pdruncfg0_set().write(|w| w.sfro_pd().set_pdruncfg0());
+                delay_loop_clocks(30, 12_000_000);
+                // Wait until SFRO disabled
+                while !sysctl0.pdruncfg0().read().sfro_pd().is_power_down() {}
+            }
+            Clocks::Ffro => {
+                sysctl0.pdruncfg0_set().write(|w| w.ffro_pd().set_pdruncfg0());
+                delay_loop_clocks(30, 12_000_000);
+                // Wait until FFRO disabled
+                while !sysctl0.pdruncfg0().read().ffro_pd().is_power_down() {}
+            }
+            _ => return Err(ClockError::ClockNotSupported),
+        }
+        Ok(())
+    })
+}
+
+/// Returns whether the main PLL has been brought up and released to run.
+///
+/// The RT6xx system PLL has no lock-detect status bit, init waits out the lock time instead.
+/// This checks everything the hardware does expose: both PLL supplies powered, the PLL out of
+/// reset and `HOLDRINGOFF_ENA` released after the lock sequence. It can't rule out a marginal
+/// lock, but catches a PLL left powered down, in reset or mid-sequence.
+#[must_use]
+pub fn main_pll_locked() -> bool {
+    // SAFETY: unsafe needed to take pointers to Sysctl0 and Clkctl0, only used for register reads
+    let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+
+    let pdruncfg0 = sysctl0.pdruncfg0().read();
+    let ctl0 = clkctl0.syspll0ctl0().read();
+    !pdruncfg0.syspllana_pd().bit_is_set()
+        && !pdruncfg0.syspllldo_pd().bit_is_set()
+        && ctl0.reset().bit_is_clear()
+        && ctl0.holdringoff_ena().bit_is_clear()
+}
+
 /// Configure the pad voltage pmc registers for all 3 vddio ranges
 fn set_pad_voltage_range() {
     // SAFETY: unsafe needed to take pointer to PMC
@@ -1340,15 +2622,490 @@ fn set_pad_voltage_range() {
 }
 
 /// Initialize AHB clock
-fn init_syscpuahb_clk(divisor: u16) {
+fn init_syscpuahb_clk(divisor: Divider) {
     // SAFETY: unsafe needed to take pointer to Clkctl0
     let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
     // SAFETY: unsafe needed to write the bits
     clkctl0
         .syscpuahbclkdiv()
-        .write(|w| unsafe { w.div().bits(divisor.saturating_sub(1) as u8) });
+        .write(|w| unsafe { w.div().bits(divisor.reg_bits()) });
 
     while clkctl0.syscpuahbclkdiv().read().reqflag().bit_is_set() {}
+
+    update_clock_cache(MAIN_CLK_HZ.load(Ordering::Relaxed));
+}
+
+/// Cause of the last reset, see [`reset_cause`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ResetCause {
+    /// Power-on or brown-out reset, all clock state was lost
+    PowerOn,
+    /// External reset pin
+    Pin,
+    /// Software reset through `AIRCR.SYSRESETREQ`
+    Software,
+    /// Watchdog 0 (the CM33 watchdog) timed out
+    Watchdog0,
+    /// Watchdog 1 (the DSP watchdog) timed out
+    Watchdog1,
+    /// No status flag set
+    Unknown,
+}
+
+/// `SYSRSTSTAT` flags, all write-1-to-clear
+const RSTSTAT_VDD_POR: u32 = 1 << 0;
+const RSTSTAT_PAD_RESET: u32 = 1 << 4;
+const RSTSTAT_ARM_RESET: u32 = 1 << 5;
+const RSTSTAT_WDT0_RESET: u32 = 1 << 6;
+const RSTSTAT_WDT1_RESET: u32 = 1 << 7;
+
+/// `SYSRSTSTAT` as latched by [`latch_reset_cause`], `u32::MAX` until then
+static RESET_STAT: AtomicU32 = AtomicU32::new(u32::MAX);
+
+/// Latches `RSTCTL0.SYSRSTSTAT` for [`reset_cause`] and clears the flags.
+///
+/// The flags are sticky across resets, so without clearing them a power-on flag would mask
+/// the cause of every later reset. Called once, first thing in clock init.
+fn latch_reset_cause() {
+    // SAFETY: unsafe needed to take pointer to Rstctl0, only to read and clear the reset status
+    let rstctl0 = unsafe { crate::pac::Rstctl0::steal() };
+    let stat = rstctl0.sysrststat().read().bits();
+    // SAFETY: writing back the set flags clears exactly those, every value is valid
+    rstctl0.sysrststat().write(|w| unsafe { w.bits(stat) });
+    RESET_STAT.store(stat, Ordering::Relaxed);
+}
+
+/// Returns the cause of the last reset.
+///
+/// The value is latched from `RSTCTL0.SYSRSTSTAT` during [`crate::init`], which then clears the
+/// flags so the next reset reports only its own cause. Before init the live register is read.
+/// If several flags are set the most severe one is reported, power-on first. Only a power-on
+/// reset guarantees the clock tree is back at its reset defaults; after any other cause the
+/// PLL and oscillators may still be running as the previous boot left them.
+#[must_use]
+pub fn reset_cause() -> ResetCause {
+    let stat = match RESET_STAT.load(Ordering::Relaxed) {
+        u32::MAX => {
+            // SAFETY: unsafe needed to take pointer to Rstctl0, only used for register reads
+            let rstctl0 = unsafe { crate::pac::Rstctl0::steal() };
+            rstctl0.sysrststat().read().bits()
+        }
+        stat => stat,
+    };
+    if stat & RSTSTAT_VDD_POR != 0 {
+        ResetCause::PowerOn
+    } else if stat & RSTSTAT_PAD_RESET != 0 {
+        ResetCause::Pin
+    } else if stat & RSTSTAT_WDT0_RESET != 0 {
+        ResetCause::Watchdog0
+    } else if stat & RSTSTAT_WDT1_RESET != 0 {
+        ResetCause::Watchdog1
+    } else if stat & RSTSTAT_ARM_RESET != 0 {
+        ResetCause::Software
+    } else {
+        ResetCause::Unknown
+    }
+}
+
+/// Set by [`lock_clocks`]
+static CLOCKS_LOCKED: AtomicBool = AtomicBool::new(false);
+
+/// Freezes the clock tree against the runtime reconfiguration APIs.
+///
+/// Afterwards every function that would change an oscillator, the PLL, the main clock or a
+/// shared divider (e.g. [`power_down`], [`with_boosted_clock`], [`set_frg_pll_div`]) returns
+/// [`ClockError::Locked`] instead. Peripheral enables are unaffected, including starting an
+/// oscillator a peripheral needs through [`ensure_running`]. There is no unlock, so the
+/// clock tree stays as configured for the rest of the run.
+pub fn lock_clocks() {
+    CLOCKS_LOCKED.store(true, Ordering::Relaxed);
+}
+
+/// Returns whether [`lock_clocks`] has been called
+#[must_use]
+pub fn clocks_locked() -> bool {
+    CLOCKS_LOCKED.load(Ordering::Relaxed)
+}
+
+/// Errors with [`ClockError::Locked`] once the clock tree has been locked
+pub(crate) fn check_unlocked() -> Result<(), ClockError> {
+    if clocks_locked() {
+        Err(ClockError::Locked)
+    } else {
+        Ok(())
+    }
+}
+
+/// Lock-free copy of the main clock rate, see [`main_clk_hz`]
+static MAIN_CLK_HZ: AtomicU32 = AtomicU32::new(0);
+/// Lock-free copy of the AHB clock rate, see [`hclk_hz`]
+static HCLK_HZ: AtomicU32 = AtomicU32::new(0);
+
+/// Refreshes the cached main and AHB clock rates after either of them changed
+fn update_clock_cache(main_clk_hz: u32) {
+    MAIN_CLK_HZ.store(main_clk_hz, Ordering::Relaxed);
+    let hclk = syscpuahb_clk_div().divide(main_clk_hz);
+    let old_hclk = HCLK_HZ.swap(hclk, Ordering::Relaxed);
+
+    // The OS event timer counts whatever its function clock delivers, so an hclk change
+    // silently shifts every timestamp and wakeup scheduled against it
+    if old_hclk != 0 && old_hclk != hclk && ostimer_clk_is_hclk() {
+        warn!("hclk changed while clocking the OS event timer, scheduled wakeups must be rescheduled");
+    }
+}
+
+/// Returns whether `OSEVENTFCLKSEL` selects hclk for the OS event timer
+fn ostimer_clk_is_hclk() -> bool {
+    // SAFETY: unsafe needed to take pointer to Clkctl1, only used for a register read
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+    clkctl1.oseventfclksel().read().sel().is_teal_free_running_clk()
+}
+
+/// Returns the OS event timer function clock rate, or `None` if its source isn't running.
+///
+/// Computed from the source selected in `OSEVENTFCLKSEL` each time, so when the timer runs
+/// from hclk this follows AHB divider changes. Switching hclk while the OS event timer drives
+/// the time base shifts its tick rate, and any scheduled wakeups must be rescheduled.
+#[must_use]
+pub fn ostimer_clk_hz() -> Option<u32> {
+    use pac::clkctl1::oseventfclksel::Sel as OsEventSel;
+    // SAFETY: unsafe needed to take pointer to Clkctl1, only used for a register read
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+    match clkctl1.oseventfclksel().read().sel().variant() {
+        Some(OsEventSel::Lposc) => is_clock_running(Clocks::Lposc).then_some(LposcFreq::Lp1m.hz()),
+        Some(OsEventSel::Rtc32khzClk) => wake_clk_hz(),
+        Some(OsEventSel::TealFreeRunningClk) => Some(hclk_hz()).filter(|&hz| hz != 0),
+        _ => None,
+    }
+}
+
+/// Returns the current main clock rate in Hz, or 0 before the clocks are initialized.
+///
+/// This is the rate of the selected main clock source, before `SYSCPUAHBCLKDIV`. The CPU and
+/// the AHB peripherals run from the divided rate, see [`cpu_clk_hz`].
+///
+/// Reads a cached value without taking a critical section, so it is safe to call from
+/// latency-sensitive interrupt handlers. The cache follows runtime main clock and AHB
+/// divider changes made through this module.
+#[must_use]
+pub fn main_clk_hz() -> u32 {
+    MAIN_CLK_HZ.load(Ordering::Relaxed)
+}
+
+/// Returns the current AHB (CPU) clock rate in Hz, or 0 before the clocks are initialized.
+///
+/// Like [`main_clk_hz`], this reads a cached value and never blocks interrupts.
+#[must_use]
+pub fn hclk_hz() -> u32 {
+    HCLK_HZ.load(Ordering::Relaxed)
+}
+
+/// Returns the CPU clock rate in Hz, or `None` before the clocks are initialized.
+///
+/// The CPU clock is the main clock divided by `SYSCPUAHBCLKDIV`, and is the same clock as hclk,
+/// so this is the rate AHB peripherals see. It equals [`main_clk_hz`] only with an AHB divider
+/// of 1, which is why the two shouldn't be used interchangeably.
+#[must_use]
+pub fn cpu_clk_hz() -> Option<u32> {
+    Some(hclk_hz()).filter(|&hz| hz != 0)
+}
+
+/// Reference clock for SysTick, see [`systick_clock`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SysTickClock {
+    /// Rate SysTick counts at, in Hz
+    pub ref_hz: u32,
+    /// Whether `SYST_CSR.CLKSOURCE` must select the external reference rather than the processor clock
+    pub use_external: bool,
+}
+
+/// Returns the clock SysTick should be configured to count, matching the clock tree.
+///
+/// If `SYSTICKFCLKSEL` routes a running clock to the SysTick external reference, that is
+/// returned with `use_external` set. Otherwise SysTick has to count the processor clock (hclk).
+/// A time driver should program `SYST_CSR.CLKSOURCE` from `use_external`.
+#[must_use]
+pub fn systick_clock() -> SysTickClock {
+    use pac::clkctl0::systickfclksel::Sel as SysTickSel;
+    // SAFETY: unsafe needed to take pointer to Clkctl0, only used for register reads
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+    let external = match clkctl0.systickfclksel().read().sel().variant() {
+        Some(SysTickSel::SystickDivClk) => {
+            Some(Divider::from_reg_bits(clkctl0.systickfclkdiv().read().div().bits()).divide(main_clk_hz()))
+                .filter(|&hz| hz != 0)
+        }
+        Some(SysTickSel::Lposc) => is_clock_running(Clocks::Lposc).then_some(LposcFreq::Lp1m.hz()),
+        Some(SysTickSel::Rtc32khz) => is_clock_running(Clocks::Rtc).then_some(RtcFreq::SubSecond32kHz.hz()),
+        Some(SysTickSel::SfroClk) => is_clock_running(Clocks::Sfro).then_some(SFRO_FREQ),
+        _ => None,
+    };
+
+    match external {
+        Some(ref_hz) => SysTickClock {
+            ref_hz,
+            use_external: true,
+        },
+        None => SysTickClock {
+            ref_hz: hclk_hz(),
+            use_external: false,
+        },
+    }
+}
+
+/// Time base candidates for embassy-time, see [`recommended_time_driver_clock`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TimeDriverSource {
+    /// RTC 32 kHz oscillator, as used by the `time-driver-rtc` feature
+    Rtc32k,
+    /// OS event timer, as used by the `time-driver-os-timer` feature
+    OsTimer,
+    /// SysTick, see [`systick_clock`]
+    SysTick,
+}
+
+/// Recommends the time base embassy-time should run from, with its rate in Hz.
+///
+/// Stable, always-on sources come first:
+///
+/// 1. [`TimeDriverSource::Rtc32k`] if the RTC oscillator runs. Crystal accurate and alive in
+///    deep sleep, but each tick is 30.5 us.
+/// 2. [`TimeDriverSource::OsTimer`] if its function clock runs. 1 us ticks from LPOSC, but LPOSC
+///    is only accurate to a few percent, and an hclk source follows AHB divider changes.
+/// 3. [`TimeDriverSource::SysTick`] otherwise. Resolution down to one hclk cycle, but it stops in
+///    deep sleep.
+#[must_use]
+pub fn recommended_time_driver_clock() -> (TimeDriverSource, u32) {
+    if is_clock_running(Clocks::Rtc) {
+        (TimeDriverSource::Rtc32k, RtcFreq::SubSecond32kHz.hz())
+    } else if let Some(hz) = ostimer_clk_hz() {
+        (TimeDriverSource::OsTimer, hz)
+    } else {
+        (TimeDriverSource::SysTick, systick_clock().ref_hz)
+    }
+}
+
+/// Flat, FFI-safe snapshot of the clock tree, see [`summary`].
+///
+/// Every field is a plain rate in Hz, with 0 meaning the clock is disabled or not running.
+/// The main PLL also reads 0 if [`main_pll_locked`] doesn't consider it locked.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ClockSummary {
+    /// Main clock
+    pub main_clk_hz: u32,
+    /// AHB (CPU) clock
+    pub hclk_hz: u32,
+    /// Main PLL clock
+    pub main_pll_hz: u32,
+    /// 48/60 MHz FFRO
+    pub ffro_hz: u32,
+    /// 16 MHz SFRO
+    pub sfro_hz: u32,
+    /// Low power oscillator
+    pub lposc_hz: u32,
+    /// External clock input
+    pub clk_in_hz: u32,
+    /// 32.768 kHz RTC oscillator
+    pub rtc_32k_hz: u32,
+}
+
+/// Returns a flat snapshot of the clock rates, or `None` before the clocks are initialized.
+///
+/// Intended for structured logging or exporting over a debug protocol, where a stable
+/// `#[repr(C)]` layout is more useful than the config types.
+#[must_use]
+pub fn summary(config: &ClockConfig) -> Option<ClockSummary> {
+    let main_clk_hz = main_clk_hz();
+    if main_clk_hz == 0 {
+        return None;
+    }
+
+    let running =
+        |clock: Clocks, rate: Result<u32, ClockError>| rate.ok().filter(|_| is_clock_running(clock)).unwrap_or(0);
+
+    Some(ClockSummary {
+        main_clk_hz,
+        hclk_hz: hclk_hz(),
+        main_pll_hz: if main_pll_locked() {
+            running(Clocks::MainPllClk, config.main_pll_clk.get_clock_rate())
+        } else {
+            0
+        },
+        ffro_hz: running(Clocks::Ffro, config.ffro.get_clock_rate()),
+        sfro_hz: running(Clocks::Sfro, config.sfro.get_clock_rate()),
+        lposc_hz: running(Clocks::Lposc, config.lposc.get_clock_rate()),
+        clk_in_hz: config.clk_in.clk_in_hz().unwrap_or(0),
+        rtc_32k_hz: if is_clock_running(Clocks::Rtc) {
+            RtcFreq::SubSecond32kHz.hz()
+        } else {
+            0
+        },
+    })
+}
+
+/// Returns the AHB clock divisor currently programmed in hardware
+fn syscpuahb_clk_div() -> Divider {
+    // SAFETY: unsafe needed to take pointer to Clkctl0, only used for a register read
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+    Divider::from_reg_bits(clkctl0.syscpuahbclkdiv().read().div().bits())
+}
+
+/// Program the `PFC0DIV` trace clock divider to divide the main clock by `divisor`
+fn set_trace_clk_div(divisor: Divider) -> Result<(), ClockError> {
+    check_unlocked()?;
+    // SAFETY: unsafe needed to take pointer to Clkctl0
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+    clkctl0.pfcdiv(0).set_div(divisor);
+    Ok(())
+}
+
+/// Maximum rate of `frg_pll`, the divided main PLL feeding the flexcomm FRGs
+const FRG_PLL_MAX_FREQ: u32 = 280_000_000;
+
+/// Reprograms `FRGPLLCLKDIV` to divide the main PLL by `div` at runtime.
+///
+/// This retunes every flexcomm whose FRG is fed by `frg_pll` at once, e.g. to move them all
+/// to a different baud rate regime. Returns the new `frg_pll` rate. Errors with
+/// [`ClockError::InvalidDiv`] for a zero divider, and [`ClockError::InvalidFrequency`] if the
+/// result would exceed 280 MHz.
+pub fn set_frg_pll_div(div: u8, config: &ClockConfig) -> Result<u32, ClockError> {
+    check_unlocked()?;
+    let div = Divider::new(u16::from(div))?;
+    let rate = div.divide(config.main_pll_clk.get_clock_rate()?);
+    if rate > FRG_PLL_MAX_FREQ {
+        return Err(ClockError::InvalidFrequency);
+    }
+
+    // SAFETY: unsafe needed to take pointer to Clkctl1, only to update FRGPLLCLKDIV
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+    clkctl1.frgpllclkdiv().set_div(div);
+    Ok(rate)
+}
+
+/// Computes the integer divider bringing `source_hz` closest to, but not above, `target_hz`.
+///
+/// Returns the divider (1 meaning undivided) and the frequency it achieves. Peripheral
+/// `*CLKDIV` registers hold the divider minus one. Errors if the target is 0 Hz, above the
+/// source, or below what `max_div` can reach.
+pub fn best_divider(source_hz: u32, target_hz: u32, max_div: u8) -> Result<(u8, u32), ClockError> {
+    if target_hz == 0 || target_hz > source_hz {
+        return Err(ClockError::InvalidFrequency);
+    }
+    let div = source_hz.div_ceil(target_hz);
+    if div > u32::from(max_div) {
+        return Err(ClockError::InvalidDiv);
+    }
+    Ok((div as u8, source_hz / div))
+}
+
+/// Checks that a source at `source_hz` divided by `I3C0FCLKDIV` gives a valid I3C function clock.
+///
+/// The I3C function clock must be a multiple of 24 MHz or 25 MHz for the bus timing to meet the
+/// spec. Returns the resulting function clock, or [`ClockError::InvalidFrequency`] if it isn't
+/// such a multiple.
+pub fn check_i3c_fclk(source_hz: u32, div: Divider) -> Result<u32, ClockError> {
+    let fclk = div.divide(source_hz);
+    if fclk != 0 && (fclk.is_multiple_of(24_000_000) || fclk.is_multiple_of(25_000_000)) {
+        Ok(fclk)
+    } else {
+        Err(ClockError::InvalidFrequency)
+    }
+}
+
+/// A `*CLKDIV` style divider register, with `DIV`, `RESET`, `HALT` and `REQFLAG` fields.
+///
+/// Encodes the update sequence once: reset the divider, write the new value with the divider
+/// running, then wait for `REQFLAG` to clear. Skipping the reset or the wait leaves the divider
+/// output unstable while it relocks. Writing `DIV` while `HALT` is set doesn't help either: the
+/// divider only latches the new value once it runs, and `REQFLAG` is what signals that.
+pub(crate) trait ClkDivider {
+    /// Programs the divider
+    fn set_div(&self, div: Divider);
+}
+
+macro_rules! impl_clk_divider {
+    ($($reg:ty),*) => {
+        $(
+            impl ClkDivider for $reg {
+                fn set_div(&self, div: Divider) {
+                    self.modify(|_, w| w.reset().set_bit());
+                    // SAFETY: unsafe needed to write the bits for div, every value is valid
+                    self.write(|w| unsafe { w.div().bits(div.reg_bits()) }.halt().clear_bit());
+                    while self.read().reqflag().bit_is_set() {}
+                }
+            }
+        )*
+    };
+}
+
+impl_clk_divider!(
+    pac::clkctl0::Pfcdiv,
+    pac::clkctl0::Adc0fclkdiv,
+    pac::clkctl0::Sctfclkdiv,
+    pac::clkctl1::Frgpllclkdiv,
+    pac::clkctl1::Clkoutdiv
+);
+
+/// Run the ARM trace clock at the hclk rate.
+///
+/// Programs `PFC0DIV` with the current `SYSCPUAHBCLKDIV` divider, so the trace clock runs at
+/// the CPU clock, which is the common setup for running SWO at full speed. Returns the
+/// resulting trace clock frequency.
+pub fn enable_trace_clock_at_hclk(main_clk: &MainClkConfig) -> Result<u32, ClockError> {
+    let rate = main_clk.ensure_main_clk()?;
+    let div = syscpuahb_clk_div();
+    set_trace_clk_div(div)?;
+    Ok(div.divide(rate))
+}
+
+/// Temporarily switches the main clock and AHB divider, runs `f`, then restores the previous state.
+///
+/// Intended for operations that need a known clock for their duration, such as flash programming.
+/// The main clock selectors and `SYSCPUAHBCLKDIV` are saved verbatim and written back once `f`
+/// returns. While switching, the AHB divider is kept at the larger of the old and new values so
+/// the AHB bus is never overclocked mid-transition.
+pub fn with_boosted_clock<R>(
+    main_clk: &mut MainClkConfig,
+    clock_src_config: &mut impl ConfigurableClock,
+    clock_src: &Clocks,
+    rate: u32,
+    ahb_div: u16,
+    f: impl FnOnce() -> R,
+) -> Result<R, ClockError> {
+    check_unlocked()?;
+    let ahb_div = Divider::new(ahb_div)?;
+    // SAFETY: unsafe needed to take pointer to Clkctl0, needed to save and restore the main clock selection
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+
+    let saved_sela = clkctl0.mainclksela().read().bits();
+    let saved_selb = clkctl0.mainclkselb().read().bits();
+    let saved_div = syscpuahb_clk_div();
+    let saved_src = main_clk.src;
+    let saved_freq = main_clk.freq.load(Ordering::Relaxed);
+
+    init_syscpuahb_clk(saved_div.max(ahb_div));
+    if let Err(e) = main_clk.set_clock_source_and_rate(clock_src_config, clock_src, rate) {
+        init_syscpuahb_clk(saved_div);
+        return Err(e);
+    }
+    init_syscpuahb_clk(ahb_div);
+
+    let result = f();
+
+    init_syscpuahb_clk(saved_div.max(ahb_div));
+    // SAFETY: unsafe needed to write back the raw selector values saved above
+    clkctl0.mainclksela().write(|w| unsafe { w.bits(saved_sela) });
+    clkctl0.mainclkselb().write(|w| unsafe { w.bits(saved_selb) });
+    main_clk.src = saved_src;
+    main_clk.freq.store(saved_freq, Ordering::Relaxed);
+    update_clock_cache(saved_freq);
+    init_syscpuahb_clk(saved_div);
+
+    Ok(result)
 }
 
 /// `ClockOut` config
@@ -1371,6 +3128,8 @@ pub enum ClkOutSrc {
     /// Low-power oscillator
     Lposc,
     /// FFRO clock
+    ///
+    /// `CLKOUTSEL0` has no FFRO/4 tap, use [`ClockOutConfig::ffro_div4`] to get one.
     Ffro,
     /// Main clock
     MainClk,
@@ -1390,17 +3149,134 @@ pub enum ClkOutSrc {
     RTC32k,
 }
 
+impl ClkOutSrc {
+    /// The main PLL PFD feeding this source, if it is a main PLL output
+    #[must_use]
+    pub const fn pll_pfd(&self) -> Option<PllPfd> {
+        match self {
+            ClkOutSrc::MainPllClk => Some(PllPfd::Pfd0),
+            ClkOutSrc::DspPllClk => Some(PllPfd::Pfd1),
+            ClkOutSrc::Aux0PllClk => Some(PllPfd::Pfd2),
+            ClkOutSrc::Aux1PllClk => Some(PllPfd::Pfd3),
+            _ => None,
+        }
+    }
+
+    /// Resolve the frequency of this source under `config`.
+    ///
+    /// Sources this crate does not yet configure (the DSP and audio clocks, and the aux PLL
+    /// outputs) return [`ClockError::ClockNotSupported`] rather than a made-up rate.
+    pub fn resolve(&self, config: &ClockConfig) -> Result<u32, ClockError> {
+        match self {
+            ClkOutSrc::None => Ok(0),
+            ClkOutSrc::Sfro => config.sfro.get_clock_rate(),
+            ClkOutSrc::ClkIn => config.clk_in.get_clock_rate(),
+            ClkOutSrc::Lposc => config.lposc.get_clock_rate(),
+            ClkOutSrc::Ffro => config.ffro.get_clock_rate(),
+            ClkOutSrc::MainClk => config.main_clk.ensure_main_clk(),
+            ClkOutSrc::MainPllClk => config.main_pll_clk.get_clock_rate(),
+            ClkOutSrc::RTC32k => {
+                if config.rtc.is_enabled() {
+                    Ok(RtcFreq::SubSecond32kHz.hz())
+                } else {
+                    Err(ClockError::ClockNotEnabled)
+                }
+            }
+            ClkOutSrc::DspMainClk
+            | ClkOutSrc::Aux0PllClk
+            | ClkOutSrc::DspPllClk
+            | ClkOutSrc::Aux1PllClk
+            | ClkOutSrc::AudioPllClk => Err(ClockError::ClockNotSupported),
+        }
+    }
+}
+
 /// Initialize the `ClkOutConfig`
+/// Returns the `CLKOUTSEL0` and `CLKOUTSEL1` selections routing `src` to CLKOUT.
+///
+/// The first stage mux only reaches CLKOUT through `CLKOUTSEL1`'s `clkoutsel0_output` input.
+/// Sources on the second stage are selected by `CLKOUTSEL1` directly, with `CLKOUTSEL0` parked
+/// on `none` so the unused first stage doesn't toggle.
+const fn route_clkout(src: ClkOutSrc) -> (pac::clkctl1::clkoutsel0::Sel, pac::clkctl1::clkoutsel1::Sel) {
+    use pac::clkctl1::clkoutsel0::Sel as Sel0;
+    use pac::clkctl1::clkoutsel1::Sel as Sel1;
+    match src {
+        ClkOutSrc::None => (Sel0::None, Sel1::None),
+        ClkOutSrc::Sfro => (Sel0::SfroClk, Sel1::Clkoutsel0Output),
+        ClkOutSrc::ClkIn => (Sel0::XtalinClk, Sel1::Clkoutsel0Output),
+        ClkOutSrc::Lposc => (Sel0::Lposc, Sel1::Clkoutsel0Output),
+        ClkOutSrc::Ffro => (Sel0::FfroClk, Sel1::Clkoutsel0Output),
+        ClkOutSrc::MainClk => (Sel0::MainClk, Sel1::Clkoutsel0Output),
+        ClkOutSrc::DspMainClk => (Sel0::DspMainClk, Sel1::Clkoutsel0Output),
+        ClkOutSrc::MainPllClk => (Sel0::None, Sel1::MainPllClk),
+        ClkOutSrc::Aux0PllClk => (Sel0::None, Sel1::Syspll0Aux0PllClk),
+        ClkOutSrc::DspPllClk => (Sel0::None, Sel1::DspPllClk),
+        ClkOutSrc::Aux1PllClk => (Sel0::None, Sel1::Syspll0Aux1PllClk),
+        ClkOutSrc::AudioPllClk => (Sel0::None, Sel1::AudioPllClk),
+        ClkOutSrc::RTC32k => (Sel0::None, Sel1::RtcClk32khz),
+    }
+}
+
 impl ClockOutConfig {
     /// Default configuration for Clock out
     #[must_use]
-    pub fn default_config() -> Self {
+    pub const fn default_config() -> Self {
         Self {
             src: ClkOutSrc::None,
             div: 0,
         }
     }
 
+    /// Clock out configuration with the given source and divider
+    ///
+    /// Returns an error if a divider is given without a source, since it would have no effect.
+    pub const fn new(src: ClkOutSrc, div: u8) -> Result<Self, ClockError> {
+        match Self::validate(src, div) {
+            Ok(()) => Ok(Self { src, div }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Clock out configuration driving FFRO/4 further divided by `div + 1`.
+    ///
+    /// `CLKOUTSEL0` can't select FFRO/4, so the /4 is folded into `CLKOUTDIV`, giving
+    /// `ffro / 4 / (1 + div)` on the pin. Handy for measuring the trimmed FFRO with a counter.
+    /// Errors if the combined divider exceeds 256, i.e. for `div` above 63.
+    pub const fn ffro_div4(div: u8) -> Result<Self, ClockError> {
+        let combined = 4 * (div as u16 + 1);
+        if combined > 256 {
+            return Err(ClockError::InvalidDiv);
+        }
+        Self::new(ClkOutSrc::Ffro, (combined - 1) as u8)
+    }
+
+    /// Output rate for a source running at `src_hz`
+    const fn output_rate(&self, src_hz: u32) -> u32 {
+        Divider::from_reg_bits(self.div).divide(src_hz)
+    }
+
+    /// Check that the source and divider are consistent with each other
+    const fn validate(src: ClkOutSrc, div: u8) -> Result<(), ClockError> {
+        if matches!(src, ClkOutSrc::None) && div != 0 {
+            Err(ClockError::InvalidDiv)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Frequency driven on the Clock Out pin, the source rate divided by `div + 1`
+    ///
+    /// `CLKOUTDIV.DIV` is 8 bits wide, so every `u8` divider is representable. An error is
+    /// returned if the divider reduces an active source to 0 Hz.
+    pub fn get_clock_rate(&self, config: &ClockConfig) -> Result<u32, ClockError> {
+        let rate = self.output_rate(self.src.resolve(config)?);
+        if rate == 0 && self.src != ClkOutSrc::None {
+            Err(ClockError::InvalidDiv)
+        } else {
+            Ok(rate)
+        }
+    }
+
     /// Enable the Clock Out output
     pub fn enable_and_reset(&mut self) -> Result<(), ClockError> {
         self.set_clkout_source_and_div(self.src, self.div)?;
@@ -1413,64 +3289,27 @@ impl ClockOutConfig {
         Ok(())
     }
 
+    /// Brings up or checks the oscillator behind `src`, see [`Self::set_clkout_source_and_div`]
+    fn ensure_source(src: ClkOutSrc) -> Result<(), ClockError> {
+        match src {
+            ClkOutSrc::Sfro => ensure_running(Clocks::Sfro),
+            ClkOutSrc::Ffro => ensure_running(Clocks::Ffro),
+            ClkOutSrc::Lposc => ensure_running(Clocks::Lposc),
+            ClkOutSrc::RTC32k if !is_clock_running(Clocks::Rtc) => Err(ClockError::ClockNotRunning),
+            // The config can't see a PFD gated after init, so check the hardware before routing
+            // or CLKOUT silently stays flat.
+            _ if src.pll_pfd().is_some_and(MainPllClkConfig::pfd_gated) => Err(ClockError::ClockNotRunning),
+            _ => Ok(()),
+        }
+    }
+
     /// Set the source of the Clock Out pin
     fn set_clkout_source(&mut self, src: ClkOutSrc) -> Result<(), ClockError> {
         // SAFETY: unsafe needed to take pointers to Clkctl1, needed to set source in HW
         let cc1 = unsafe { pac::Clkctl1::steal() };
-        match src {
-            ClkOutSrc::None => {
-                cc1.clkoutsel0().write(|w| w.sel().none());
-                cc1.clkoutsel1().write(|w| w.sel().none());
-            }
-            ClkOutSrc::Sfro => {
-                cc1.clkoutsel0().write(|w| w.sel().sfro_clk());
-                cc1.clkoutsel1().write(|w| w.sel().clkoutsel0_output());
-            }
-            ClkOutSrc::ClkIn => {
-                cc1.clkoutsel0().write(|w| w.sel().xtalin_clk());
-                cc1.clkoutsel1().write(|w| w.sel().clkoutsel0_output());
-            }
-            ClkOutSrc::Lposc => {
-                cc1.clkoutsel0().write(|w| w.sel().lposc());
-                cc1.clkoutsel1().write(|w| w.sel().clkoutsel0_output());
-            }
-            ClkOutSrc::Ffro => {
-                cc1.clkoutsel0().write(|w| w.sel().ffro_clk());
-                cc1.clkoutsel1().write(|w| w.sel().clkoutsel0_output());
-            }
-            ClkOutSrc::MainClk => {
-                cc1.clkoutsel0().write(|w| w.sel().main_clk());
-                cc1.clkoutsel1().write(|w| w.sel().clkoutsel0_output());
-            }
-            ClkOutSrc::DspMainClk => {
-                cc1.clkoutsel0().write(|w| w.sel().dsp_main_clk());
-                cc1.clkoutsel1().write(|w| w.sel().clkoutsel0_output());
-            }
-            ClkOutSrc::MainPllClk => {
-                cc1.clkoutsel0().write(|w| w.sel().none());
-                cc1.clkoutsel1().write(|w| w.sel().main_pll_clk());
-            }
-            ClkOutSrc::Aux0PllClk => {
-                cc1.clkoutsel0().write(|w| w.sel().none());
-                cc1.clkoutsel1().write(|w| w.sel().syspll0_aux0_pll_clk());
-            }
-            ClkOutSrc::DspPllClk => {
-                cc1.clkoutsel0().write(|w| w.sel().none());
-                cc1.clkoutsel1().write(|w| w.sel().dsp_pll_clk());
-            }
-            ClkOutSrc::AudioPllClk => {
-                cc1.clkoutsel0().write(|w| w.sel().none());
-                cc1.clkoutsel1().write(|w| w.sel().audio_pll_clk());
-            }
-            ClkOutSrc::Aux1PllClk => {
-                cc1.clkoutsel0().write(|w| w.sel().none());
-                cc1.clkoutsel1().write(|w| w.sel().syspll0_aux1_pll_clk());
-            }
-            ClkOutSrc::RTC32k => {
-                cc1.clkoutsel0().write(|w| w.sel().none());
-                cc1.clkoutsel1().write(|w| w.sel().rtc_clk_32khz());
-            }
-        }
+        let (sel0, sel1) = route_clkout(src);
+        cc1.clkoutsel0().write(|w| w.sel().variant(sel0));
+        cc1.clkoutsel1().write(|w| w.sel().variant(sel1));
         self.src = src;
         Ok(())
     }
@@ -1480,18 +3319,67 @@ impl ClockOutConfig {
     /// ...
     /// bits(255)-> divide by 256
     pub fn set_clkout_divider(&self, div: u8) -> Result<(), ClockError> {
+        Self::validate(self.src, div)?;
         // don't wait for clock to be ready if there's no source
         if self.src != ClkOutSrc::None {
             let cc1 = unsafe { pac::Clkctl1::steal() };
-
-            cc1.clkoutdiv()
-                .modify(|_, w| unsafe { w.div().bits(div) }.halt().clear_bit());
-            while cc1.clkoutdiv().read().reqflag().bit_is_set() {}
+            cc1.clkoutdiv().set_div(Divider::from_reg_bits(div));
         }
         Ok(())
     }
+    /// Reads back the source currently driving the Clock Out pin from `CLKOUTSEL0`/`CLKOUTSEL1`.
+    ///
+    /// `CLKOUTSEL1` selects either a PLL/RTC clock directly or the output of `CLKOUTSEL0`,
+    /// so the first stage is only consulted when the second stage passes it through.
+    #[must_use]
+    pub fn current_source() -> ClkOutSrc {
+        // SAFETY: unsafe needed to take pointer to Clkctl1, only used for register reads
+        let cc1 = unsafe { pac::Clkctl1::steal() };
+        let sel1 = cc1.clkoutsel1().read().sel();
+        if sel1.is_clkoutsel0_output() {
+            let sel0 = cc1.clkoutsel0().read().sel();
+            if sel0.is_sfro_clk() {
+                ClkOutSrc::Sfro
+            } else if sel0.is_xtalin_clk() {
+                ClkOutSrc::ClkIn
+            } else if sel0.is_lposc() {
+                ClkOutSrc::Lposc
+            } else if sel0.is_ffro_clk() {
+                ClkOutSrc::Ffro
+            } else if sel0.is_main_clk() {
+                ClkOutSrc::MainClk
+            } else if sel0.is_dsp_main_clk() {
+                ClkOutSrc::DspMainClk
+            } else {
+                ClkOutSrc::None
+            }
+        } else if sel1.is_main_pll_clk() {
+            ClkOutSrc::MainPllClk
+        } else if sel1.is_syspll0_aux0_pll_clk() {
+            ClkOutSrc::Aux0PllClk
+        } else if sel1.is_dsp_pll_clk() {
+            ClkOutSrc::DspPllClk
+        } else if sel1.is_syspll0_aux1_pll_clk() {
+            ClkOutSrc::Aux1PllClk
+        } else if sel1.is_audio_pll_clk() {
+            ClkOutSrc::AudioPllClk
+        } else if sel1.is_rtc_clk_32khz() {
+            ClkOutSrc::RTC32k
+        } else {
+            ClkOutSrc::None
+        }
+    }
+
     /// set the source and divider for the clockout pin
+    ///
+    /// The internal oscillators (SFRO, FFRO and LPOSC) are powered up on demand when selected.
+    /// The other sources must already run: the RTC 32 kHz oscillator and the main PLL outputs
+    /// error with [`ClockError::ClockNotRunning`] otherwise. clk_in is external and can't be
+    /// observed, so it has to be enabled in the [`ClockConfig`] first.
     pub fn set_clkout_source_and_div(&mut self, src: ClkOutSrc, div: u8) -> Result<(), ClockError> {
+        check_unlocked()?;
+        Self::validate(src, div)?;
+        Self::ensure_source(src)?;
         self.set_clkout_source(src)?;
 
         self.set_clkout_divider(div)?;
@@ -1502,7 +3390,9 @@ impl ClockOutConfig {
 
 /// Using the config, enables all desired clocks to desired clock rates
 fn init_clock_hw(config: ClockConfig) -> Result<(), ClockError> {
-    config.rtc.enable_and_reset()?;
+    if config.needs_32k_osc() {
+        config.rtc.enable_and_reset()?;
+    }
     config.lposc.enable_and_reset()?;
     config.ffro.enable_and_reset()?;
     config.sfro.enable_and_reset()?;
@@ -1516,12 +3406,17 @@ fn init_clock_hw(config: ClockConfig) -> Result<(), ClockError> {
     // so this should be fine.
     MainClkConfig::reset_main_clk();
 
+    // init_syspll always feeds the PLL from FFRO / 2
+    if config.main_pll_clk.is_enabled() {
+        check_init_step("main_pll_clk", Clocks::Ffro)?;
+    }
     config.main_pll_clk.enable_and_reset()?;
 
     // Move FLEXSPI clock source from main clock to FFRO to avoid instruction/data fetch issue in XIP when
     // updating PLL and main clock.
     // SAFETY: unsafe needed to take pointers to Clkctl0
     let cc0 = unsafe { pac::Clkctl0::steal() };
+    check_init_step("flexspi_fclk", Clocks::Ffro)?;
     cc0.flexspifclksel().write(|w| w.sel().ffro_clk());
 
     // Move ESPI clock source to FFRO
@@ -1531,19 +3426,62 @@ fn init_clock_hw(config: ClockConfig) -> Result<(), ClockError> {
     }
 
     // Increase divisor to safe value.
-    init_syscpuahb_clk(256);
+    init_syscpuahb_clk(Divider::MAX);
 
+    check_init_step("main_clk", config.main_clk.src.into())?;
     config.main_clk.enable_and_reset()?;
+    update_clock_cache(config.main_clk.freq.load(Ordering::Relaxed));
+    debug_assert!(main_clk_hz() != 0, "clock init: main_clk_hz not populated before hclk");
 
     // Set divisor to final value.
-    init_syscpuahb_clk(config.main_clk.div_int.load(Ordering::Relaxed) as u16);
+    init_syscpuahb_clk(config.main_clk.ahb_div()?);
 
-    config.sys_clk.update_sys_core_clock();
+    // Report the rate from the divider actually in effect rather than the requested one
+    let hclk_freq = config.hclk.get_clock_rate(config.main_clk.freq.load(Ordering::Relaxed));
+    config.sys_clk.update_sys_core_clock(hclk_freq);
+
+    let main_clk_freq = config.main_clk.freq.load(Ordering::Relaxed);
+    if let Ok(actual) = config.main_clk.ensure_main_clk() {
+        report_rounding("main_clk", main_clk_freq, actual);
+    }
+    if let Ok(div) = config.main_clk.ahb_div() {
+        report_rounding("hclk", main_clk_freq.div_ceil(u32::from(div.factor())), hclk_freq);
+    }
     Ok(())
 }
 
+/// Self-check of the `init_clock_hw` ordering.
+///
+/// Each step switches onto clocks an earlier step brought up, so a reordering refactor that
+/// breaks this would otherwise only show up as a hang or a wrong rate. Logs the step and the
+/// clock that isn't running yet, and fails init with [`ClockError::ClockNotRunning`].
+fn check_init_step(step: &str, requires: Clocks) -> Result<(), ClockError> {
+    if is_clock_running(requires) {
+        Ok(())
+    } else {
+        error!("clock init: {} needs {:?} running first", step, requires);
+        Err(ClockError::ClockNotRunning)
+    }
+}
+
+/// Logs when integer dividers left `clock` at a rate other than the one requested.
+///
+/// Purely diagnostic, so e.g. a 500.21 MHz main clock from a requested 500 MHz doesn't
+/// come as a surprise.
+fn report_rounding(clock: &str, requested_hz: u32, actual_hz: u32) {
+    if requested_hz != actual_hz {
+        info!(
+            "{} rounded to {} (requested {})",
+            clock,
+            FreqDisplay(actual_hz),
+            FreqDisplay(requested_hz)
+        );
+    }
+}
+
 /// SAFETY: must be called exactly once at bootup
 pub(crate) unsafe fn init(config: ClockConfig) -> Result<(), ClockError> {
+    latch_reset_cause();
     init_clock_hw(config)?;
 
     // set VDDIO ranges 0-2
@@ -1553,9 +3491,91 @@ pub(crate) unsafe fn init(config: ClockConfig) -> Result<(), ClockError> {
 
 ///Trait to expose perph clocks
 trait SealedSysconPeripheral {
+    /// Minimum rated function clock in Hz, 0 if unchecked
+    const MIN_FCLK: u32 = 0;
+    /// Maximum rated function clock in Hz, 0 if unchecked
+    const MAX_FCLK: u32 = 0;
+    /// Index into [`FCLK_CACHE`] for the peripherals whose driver records its function clock
+    const FCLK_SLOT: Option<usize>;
+
     fn enable_perph_clock();
     fn reset_perph();
     fn disable_perph_clock();
+    fn is_perph_clock_enabled() -> bool;
+}
+
+/// One slot per peripheral whose driver records its function clock, see [`fclk_slot`]
+const FCLK_SLOTS: usize = 13;
+
+/// Last function clock recorded per peripheral, 0 meaning none, see [`try_clock_freq`]
+static FCLK_CACHE: [AtomicU32; FCLK_SLOTS] = [const { AtomicU32::new(0) }; FCLK_SLOTS];
+
+/// Maps a peripheral to its [`FCLK_CACHE`] slot, `None` for the ones nothing records
+macro_rules! fclk_slot {
+    (FLEXCOMM0) => {
+        Some(0)
+    };
+    (FLEXCOMM1) => {
+        Some(1)
+    };
+    (FLEXCOMM2) => {
+        Some(2)
+    };
+    (FLEXCOMM3) => {
+        Some(3)
+    };
+    (FLEXCOMM4) => {
+        Some(4)
+    };
+    (FLEXCOMM5) => {
+        Some(5)
+    };
+    (FLEXCOMM6) => {
+        Some(6)
+    };
+    (FLEXCOMM7) => {
+        Some(7)
+    };
+    (FLEXCOMM14) => {
+        Some(8)
+    };
+    (FLEXCOMM15) => {
+        Some(9)
+    };
+    (ADC0) => {
+        Some(10)
+    };
+    (ESPI) => {
+        Some(11)
+    };
+    (HASHCRYPT) => {
+        Some(12)
+    };
+    ($peripheral:ident) => {
+        None
+    };
+}
+
+/// Records `hz` as the function clock of peripheral `T`, for [`try_clock_freq`].
+///
+/// The flexcomm, ADC, eSPI and Hashcrypt drivers call this when they enable their peripheral,
+/// [`refresh`] does it after a rate change, and [`disable`] clears the entry. Other
+/// peripherals have no slot, so recording for them is a no-op.
+pub(crate) fn record_clock_freq<T: SysconPeripheral>(hz: u32) {
+    if let Some(slot) = T::FCLK_SLOT {
+        FCLK_CACHE[slot].store(hz, Ordering::Relaxed);
+    }
+}
+
+/// Returns the function clock last recorded for peripheral `T`, `None` if there is none.
+///
+/// Reads a cached value without touching the clock tree, so it is cheap enough for
+/// diagnostics and for drivers that need the rate another driver brought up.
+#[must_use]
+pub fn try_clock_freq<T: SysconPeripheral>() -> Option<u32> {
+    T::FCLK_SLOT
+        .map(|slot| FCLK_CACHE[slot].load(Ordering::Relaxed))
+        .filter(|&hz| hz != 0)
 }
 
 /// Clock and Reset control for peripherals
@@ -1571,6 +3591,48 @@ pub fn enable_and_reset<T: SysconPeripheral>() {
     T::reset_perph();
 }
 
+/// Enables and resets peripheral `T`, which is about to run from function clock `fclk`.
+///
+/// `fclk` is range checked with [`check_fclk`] before the clock is ungated, so a peripheral
+/// clocked outside its rating is left disabled and reported as [`ClockError::InvalidFrequency`].
+/// On success `fclk` is recorded for [`try_clock_freq`]. The peripheral must not be in use.
+pub fn enable_and_reset_at<T: SysconPeripheral>(fclk: u32) -> Result<(), ClockError> {
+    check_fclk::<T>(fclk)?;
+    enable_and_reset::<T>();
+    record_clock_freq::<T>(fclk);
+    Ok(())
+}
+
+/// Enables peripheral `T`, runs `configure` with its clock running, then resets it.
+///
+/// If `configure` fails, e.g. because the function clock it selects isn't available, the
+/// peripheral clock is gated again before returning the error. A failed enable then leaves `T`
+/// cleanly disabled rather than clocked, half configured and never reset. The peripheral must
+/// not be in use.
+pub fn try_enable_and_reset<T: SysconPeripheral, R>(
+    configure: impl FnOnce() -> Result<R, ClockError>,
+) -> Result<R, ClockError> {
+    T::enable_perph_clock();
+    match configure() {
+        Ok(r) => {
+            T::reset_perph();
+            Ok(r)
+        }
+        Err(e) => {
+            T::disable_perph_clock();
+            Err(e)
+        }
+    }
+}
+
+/// Enables and resets the peripheral behind `peri`.
+///
+/// Same as [`enable_and_reset`], but taking the peripheral handle ties the reset to exclusive
+/// ownership of the peripheral, so it can't happen under a driver still using it.
+pub fn enable_and_reset_peri<T: SysconPeripheral + PeripheralType>(_peri: &mut Peri<'_, T>) {
+    enable_and_reset::<T>();
+}
+
 /// Enables peripheral `T`.
 pub fn enable<T: SysconPeripheral>() {
     T::enable_perph_clock();
@@ -1588,10 +3650,180 @@ pub fn reset<T: SysconPeripheral>() {
 /// Peripheral must not be in use.
 pub fn disable<T: SysconPeripheral>() {
     T::disable_perph_clock();
+    record_clock_freq::<T>(0);
+}
+
+/// Enables and resets peripheral `T`, gating its clock again when the returned guard is dropped.
+///
+/// Use [`enable_and_reset`] instead where the clock must outlive the driver.
+pub fn enable_guarded<T: SysconPeripheral>() -> EnabledPeripheral<T> {
+    enable_and_reset::<T>();
+    EnabledPeripheral {
+        _phantom: core::marker::PhantomData,
+    }
+}
+
+/// Guard returned by [`enable_guarded`], keeping the clock of peripheral `T` enabled while alive.
+#[must_use]
+pub struct EnabledPeripheral<T: SysconPeripheral> {
+    _phantom: core::marker::PhantomData<T>,
+}
+
+impl<T: SysconPeripheral> Drop for EnabledPeripheral<T> {
+    fn drop(&mut self) {
+        // No-op if the clock was already gated through the manual API, which cleared the cache
+        critical_section::with(|_| {
+            if T::is_perph_clock_enabled() {
+                disable::<T>();
+            }
+        });
+    }
 }
+
+/// Selects the 48/60m_irc (FFRO) as the eSPI function clock, returning its rate.
+///
+/// `ESPIFCLKSEL` can only select the FFRO or nothing, so the FFRO must be running before the
+/// eSPI interface will respond.
+#[cfg(feature = "_espi")]
+pub fn enable_espi_fclk() -> Result<u32, ClockError> {
+    if !is_clock_running(Clocks::Ffro) {
+        return Err(ClockError::ClockNotRunning);
+    }
+    // SAFETY: unsafe needed to take pointer to Clkctl0, only to select the eSPI function clock
+    let clkctl0 = unsafe { crate::pac::Clkctl0::steal() };
+    clkctl0.espiclksel().write(|w| w.sel().use_48_60m());
+    Ok(FfroConfig::trimmed_freq().hz())
+}
+
+/// Lowest DMIC function clock still giving a usable speech band.
+///
+/// A PDM microphone needs roughly 64 clocks per PCM sample, so this corresponds to 16 kHz capture.
+const DMIC_SPEECH_MIN_FCLK: u32 = 1_024_000;
+
+/// Selects the 32 kHz wake clock as the DMIC function clock, returning its rate.
+///
+/// This is the always-on path for low-power voice activity detection (e.g. keyword spotting
+/// wake-up), distinct from the full-bandwidth DMIC path. At 31.25 or 32.768 kHz the audio
+/// bandwidth is far too low for intelligible capture, only the DMIC's hardware voice activity
+/// detector is useful, so a warning is logged. Errors with [`ClockError::ClockNotRunning`] if
+/// the wake clock's source isn't running.
+pub fn enable_dmic_wake_clk() -> Result<u32, ClockError> {
+    let rate = wake_clk_hz().ok_or(ClockError::ClockNotRunning)?;
+
+    // SAFETY: unsafe needed to take pointer to Clkctl1, only to select the DMIC function clock
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+    clkctl1.dmic0fclksel().write(|w| w.sel().wake_clk_32khz());
+
+    if rate < DMIC_SPEECH_MIN_FCLK {
+        warn!(
+            "DMIC clocked at {}, too low for audio capture, only voice activity detection works",
+            FreqDisplay(rate)
+        );
+    }
+    Ok(rate)
+}
+
+/// FlexSPI function clock source, selected by `FLEXSPIFCLKSEL`.
+///
+/// See [`crate::flexspi::peripheral::FlexSpi::reconfigure_clock`] for switching it while
+/// executing from flash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FlexspiClkSrc {
+    /// Main clock
+    MainClk,
+    /// Main PLL clock (PFD0)
+    MainPllClk,
+    /// 48/60m_irc (FFRO)
+    Ffro,
+}
+
+impl FlexspiClkSrc {
+    /// `FLEXSPIFCLKSEL.SEL` selection of this source
+    pub(crate) const fn sel(self) -> pac::clkctl0::flexspifclksel::Sel {
+        use pac::clkctl0::flexspifclksel::Sel;
+        match self {
+            FlexspiClkSrc::MainClk => Sel::MainClk,
+            FlexspiClkSrc::MainPllClk => Sel::MainSysPllClk,
+            FlexspiClkSrc::Ffro => Sel::FfroClk,
+        }
+    }
+
+    /// Returns the source rate, erroring if it isn't running.
+    ///
+    /// Switching FlexSPI to a stopped clock stalls every flash access, so this is checked
+    /// before touching the mux.
+    pub(crate) fn rate(self, config: &ClockConfig) -> Result<u32, ClockError> {
+        match self {
+            FlexspiClkSrc::MainClk => config.main_clk.ensure_main_clk(),
+            FlexspiClkSrc::MainPllClk if !main_pll_locked() => Err(ClockError::ClockNotRunning),
+            FlexspiClkSrc::MainPllClk => config.main_pll_clk.get_clock_rate(),
+            FlexspiClkSrc::Ffro if !is_clock_running(Clocks::Ffro) => Err(ClockError::ClockNotRunning),
+            FlexspiClkSrc::Ffro => Ok(FfroConfig::trimmed_freq().hz()),
+        }
+    }
+}
+
+/// Rate of `aux1_pll_clk`, the main PLL's PFD3 output divided by the aux1 divider.
+///
+/// Feed this to [`crate::adc::ClockSource::Aux1Pll`] to clock the ADC from the PLL. Errors with
+/// [`ClockError::ClockNotRunning`] if PFD3 is gated.
+pub fn aux1_pll_clk_rate(main_pll: &MainPllClkConfig, clk_in: &ClkInConfig) -> Result<u32, ClockError> {
+    if MainPllClkConfig::pfd_gated(PllPfd::Pfd3) {
+        return Err(ClockError::ClockNotRunning);
+    }
+    let pfd3 = MainPllClkConfig::pfd_freq(u64::from(main_pll.vco_freq(clk_in)?), main_pll.pfd3)?;
+    Ok(Divider::from_reg_bits(main_pll.aux1_div).divide(pfd3))
+}
+
+/// Enables and resets the analog comparator, powering up its analog block.
+///
+/// The ACMP has no function clock mux, but enabling only its bus clock is not enough: without
+/// the analog block powered, the comparator output is undefined.
+pub fn enable_acmp() {
+    // SAFETY: unsafe needed to take pointer to Sysctl0, only to power up the ACMP analog block
+    let sysctl0 = unsafe { crate::pac::Sysctl0::steal() };
+    sysctl0.pdruncfg0_clr().write(|w| w.acmp_pd().set_bit());
+
+    enable_and_reset::<crate::peripherals::ACMP>();
+}
+
+/// Checks that `fclk` is within the rated function clock of peripheral `T`.
+///
+/// [`enable_and_reset_at`] and [`refresh`] run this on the function clock they are given, so
+/// drivers going through them get e.g. clocking a peripheral outside its rating as an error.
+pub fn check_fclk<T: SysconPeripheral>(fclk: u32) -> Result<(), ClockError> {
+    if fclk < T::MIN_FCLK || (T::MAX_FCLK != 0 && fclk > T::MAX_FCLK) {
+        Err(ClockError::InvalidFrequency)
+    } else {
+        Ok(())
+    }
+}
+
+/// Recomputes the function clock of peripheral `T` after a source changed rate at runtime.
+///
+/// Changing e.g. the FFRO trim or the AHB divider leaves any rate a driver derived at
+/// construction silently stale. `compute` is the driver's own function clock computation
+/// (reading the mux and divider against the updated [`ClockConfig`], reprogramming a divider
+/// if it needs to), and the result is range checked with [`check_fclk`] before it's returned
+/// for the driver to retune its baud rate or timing registers.
+pub fn refresh<T: SysconPeripheral>(compute: impl FnOnce() -> Result<u32, ClockError>) -> Result<u32, ClockError> {
+    let fclk = compute()?;
+    check_fclk::<T>(fclk)?;
+    record_clock_freq::<T>(fclk);
+    Ok(fclk)
+}
+
 macro_rules! impl_perph_clk {
     ($peripheral:ident, $clkctl:ident, $clkreg:ident, $rstctl:ident, $rstreg:ident, $bit:expr) => {
+        impl_perph_clk!($peripheral, $clkctl, $clkreg, $rstctl, $rstreg, $bit, 0, 0);
+    };
+    ($peripheral:ident, $clkctl:ident, $clkreg:ident, $rstctl:ident, $rstreg:ident, $bit:expr, $min_fclk:expr, $max_fclk:expr) => {
         impl SealedSysconPeripheral for crate::peripherals::$peripheral {
+            const MIN_FCLK: u32 = $min_fclk;
+            const MAX_FCLK: u32 = $max_fclk;
+            const FCLK_SLOT: Option<usize> = fclk_slot!($peripheral);
+
             fn enable_perph_clock() {
                 // SAFETY: unsafe needed to take pointers to Rstctl1 and Clkctl1
                 let cc1 = unsafe { pac::$clkctl::steal() };
@@ -1621,6 +3853,13 @@ macro_rules! impl_perph_clk {
                     cc1.[<$clkreg _clr>]().write(|w| unsafe { w.bits(1 << $bit) });
                 }
             }
+
+            fn is_perph_clock_enabled() -> bool {
+                // SAFETY: unsafe needed to take pointer to the clock control block, only used for a register read
+                let cc1 = unsafe { pac::$clkctl::steal() };
+
+                cc1.$clkreg().read().bits() & (1 << $bit) != 0
+            }
         }
 
         impl SysconPeripheral for crate::peripherals::$peripheral {}
@@ -1636,7 +3875,8 @@ macro_rules! impl_perph_clk {
 
 impl_perph_clk!(PIMCTL, Clkctl1, pscctl2, Rstctl1, prstctl2, 31);
 impl_perph_clk!(ACMP, Clkctl0, pscctl1, Rstctl0, prstctl1, 15);
-impl_perph_clk!(ADC0, Clkctl0, pscctl1, Rstctl0, prstctl1, 16);
+// conversions are only valid within the ADC's rated function clock window
+impl_perph_clk!(ADC0, Clkctl0, pscctl1, Rstctl0, prstctl1, 16, 1_000_000, 24_000_000);
 impl_perph_clk!(CASPER, Clkctl0, pscctl0, Rstctl0, prstctl0, 9);
 impl_perph_clk!(CRC, Clkctl1, pscctl1, Rstctl1, prstctl1, 16);
 impl_perph_clk!(CTIMER0_COUNT_CHANNEL0, Clkctl1, pscctl2, Rstctl1, prstctl2, 0);
@@ -1691,3 +3931,365 @@ impl_perph_clk!(USDHC1, Clkctl0, pscctl1, Rstctl0, prstctl1, 3);
 impl_perph_clk!(UTICK0, Clkctl0, pscctl2, Rstctl0, prstctl2, 0);
 impl_perph_clk!(WDT0, Clkctl0, pscctl2, Rstctl0, prstctl2, 1);
 impl_perph_clk!(WDT1, Clkctl1, pscctl2, Rstctl1, prstctl2, 10);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clkout_resolve() {
+        let config = ClockConfig::crystal();
+
+        assert_eq!(ClkOutSrc::None.resolve(&config), Ok(0));
+        assert_eq!(ClkOutSrc::Sfro.resolve(&config), Ok(SFRO_FREQ));
+        assert_eq!(ClkOutSrc::Lposc.resolve(&config), Ok(LposcFreq::Lp1m.hz()));
+        assert_eq!(ClkOutSrc::RTC32k.resolve(&config), Ok(RtcFreq::SubSecond32kHz.hz()));
+        assert_eq!(
+            ClkOutSrc::AudioPllClk.resolve(&config),
+            Err(ClockError::ClockNotSupported)
+        );
+    }
+
+    #[test]
+    fn test_clkout_pll_pfd() {
+        assert_eq!(ClkOutSrc::MainPllClk.pll_pfd(), Some(PllPfd::Pfd0));
+        assert_eq!(ClkOutSrc::DspPllClk.pll_pfd(), Some(PllPfd::Pfd1));
+        assert_eq!(ClkOutSrc::Aux0PllClk.pll_pfd(), Some(PllPfd::Pfd2));
+        assert_eq!(ClkOutSrc::Aux1PllClk.pll_pfd(), Some(PllPfd::Pfd3));
+        assert_eq!(ClkOutSrc::AudioPllClk.pll_pfd(), None);
+    }
+
+    #[test]
+    fn test_route_clkout() {
+        // Mux positions from the CLKOUT diagram, CLKOUTSEL0 feeds CLKOUTSEL1 input 0 and both
+        // stages use 7 for "none"
+        let expected = [
+            (ClkOutSrc::None, 7, 7),
+            (ClkOutSrc::Sfro, 0, 0),
+            (ClkOutSrc::ClkIn, 1, 0),
+            (ClkOutSrc::Lposc, 2, 0),
+            (ClkOutSrc::Ffro, 3, 0),
+            (ClkOutSrc::MainClk, 4, 0),
+            (ClkOutSrc::DspMainClk, 6, 0),
+            (ClkOutSrc::MainPllClk, 7, 1),
+            (ClkOutSrc::Aux0PllClk, 7, 2),
+            (ClkOutSrc::DspPllClk, 7, 3),
+            (ClkOutSrc::Aux1PllClk, 7, 4),
+            (ClkOutSrc::AudioPllClk, 7, 5),
+            (ClkOutSrc::RTC32k, 7, 6),
+        ];
+        for (src, sel0_bits, sel1_bits) in expected {
+            let (sel0, sel1) = route_clkout(src);
+            assert_eq!(sel0 as u8, sel0_bits, "{src:?}");
+            assert_eq!(sel1 as u8, sel1_bits, "{src:?}");
+        }
+    }
+
+    #[test]
+    fn test_needs_32k_osc() {
+        let mut config = ClockConfig::crystal();
+        assert!(config.needs_32k_osc());
+
+        config.rtc.state = State::Disabled;
+        assert_eq!(config.needs_32k_osc(), cfg!(feature = "time-driver-rtc"));
+
+        config.main_clk.src = MainClkSrc::RTC32k;
+        assert!(config.needs_32k_osc());
+    }
+
+    #[test]
+    fn test_fclk_slots_unique() {
+        assert_eq!(<crate::peripherals::CRC as SealedSysconPeripheral>::FCLK_SLOT, None);
+        let slots = [
+            <crate::peripherals::ADC0 as SealedSysconPeripheral>::FCLK_SLOT,
+            <crate::peripherals::FLEXCOMM0 as SealedSysconPeripheral>::FCLK_SLOT,
+            <crate::peripherals::FLEXCOMM15 as SealedSysconPeripheral>::FCLK_SLOT,
+            <crate::peripherals::FLEXCOMM7 as SealedSysconPeripheral>::FCLK_SLOT,
+            <crate::peripherals::HASHCRYPT as SealedSysconPeripheral>::FCLK_SLOT,
+        ]
+        .map(Option::unwrap);
+        for (i, a) in slots.iter().enumerate() {
+            assert!(*a < FCLK_SLOTS);
+            assert!(slots[i + 1..].iter().all(|b| b != a));
+        }
+    }
+
+    #[test]
+    fn test_clkout_max_divider() {
+        let config = ClockConfig::crystal();
+
+        let clk_out = ClockOutConfig::new(ClkOutSrc::Sfro, u8::MAX).unwrap();
+        assert_eq!(clk_out.get_clock_rate(&config), Ok(SFRO_FREQ / 256));
+
+        assert_eq!(
+            ClockOutConfig::new(ClkOutSrc::None, u8::MAX).err(),
+            Some(ClockError::InvalidDiv)
+        );
+    }
+
+    #[test]
+    fn test_divider_encoding() {
+        assert_eq!(Divider::new(0), Err(ClockError::InvalidDiv));
+        assert_eq!(Divider::new(257), Err(ClockError::InvalidDiv));
+
+        assert_eq!(Divider::ONE.reg_bits(), 0);
+        assert_eq!(Divider::MAX.reg_bits(), u8::MAX);
+        assert_eq!(Divider::new(12).unwrap().reg_bits(), 11);
+        assert_eq!(Divider::from_reg_bits(11).factor(), 12);
+        assert_eq!(Divider::new(4).unwrap().divide(48_000_000), 12_000_000);
+    }
+
+    #[test]
+    fn test_clkout_ffro_div4() {
+        let clk_out = ClockOutConfig::ffro_div4(0).unwrap();
+        assert_eq!(clk_out.output_rate(FfroFreq::Ffro48m.hz()), 12_000_000);
+
+        let clk_out = ClockOutConfig::ffro_div4(1).unwrap();
+        assert_eq!(clk_out.output_rate(FfroFreq::Ffro48m.hz()), 6_000_000);
+
+        let clk_out = ClockOutConfig::ffro_div4(63).unwrap();
+        assert_eq!(clk_out.output_rate(FfroFreq::Ffro48m.hz()), 187_500);
+        assert_eq!(ClockOutConfig::ffro_div4(64).err(), Some(ClockError::InvalidDiv));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(ClockConfig::crystal().validate().is_ok());
+
+        let mut config = ClockConfig::crystal();
+        config.main_pll_clk.pfd0 = 36;
+        assert_eq!(config.validate().err(), Some(ClockError::InvalidDiv));
+
+        // init ignores the stored multiplier, so it doesn't fail validation
+        let config = ClockConfig::crystal();
+        config.main_pll_clk.mult.store(18, Ordering::Relaxed);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_main_clk_mux_sel() {
+        use pac::clkctl0::mainclksela::Sel as SelA;
+        use pac::clkctl0::mainclkselb::Sel as SelB;
+
+        // Mux positions from the main clock diagram, first stage (SELA) feeds SELB input 0
+        let expected = [
+            (MainClkSrc::FFROdiv4, Some(SelA::FfroDiv4), SelB::Main1stClk, 0, Some(0)),
+            (MainClkSrc::ClkIn, Some(SelA::SysxtalClk), SelB::Main1stClk, 0, Some(1)),
+            (MainClkSrc::Lposc, Some(SelA::Lposc), SelB::Main1stClk, 0, Some(2)),
+            (MainClkSrc::FFRO, Some(SelA::FfroClk), SelB::Main1stClk, 0, Some(3)),
+            (MainClkSrc::SFRO, None, SelB::SfroClk, 1, None),
+            (MainClkSrc::PllMain, None, SelB::MainPllClk, 2, None),
+            (MainClkSrc::RTC32k, None, SelB::Rtc32kClk, 3, None),
+        ];
+        for (src, sela, selb, selb_bits, sela_bits) in expected {
+            assert_eq!(src.mux_sel(), (sela, selb), "{src:?}");
+            assert_eq!(selb as u8, selb_bits, "{src:?}");
+            assert_eq!(sela.map(|s| s as u8), sela_bits, "{src:?}");
+
+            // Every source but FFRO / 4 maps back to itself through `Clocks`
+            if src != MainClkSrc::FFROdiv4 {
+                assert_eq!(MainClkSrc::try_from(Clocks::from(src)), Ok(src));
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_graph() {
+        assert_eq!(ClockConfig::crystal().validate_graph(), Ok(()));
+
+        let mut config = ClockConfig::crystal();
+        config.ffro.state = State::Disabled;
+        assert_eq!(
+            config.validate_graph(),
+            Err(BrokenLink {
+                clock: Clocks::MainPllClk,
+                source: Clocks::Ffro,
+                reason: ClockError::ClockNotEnabled,
+            })
+        );
+
+        let mut config = ClockConfig::crystal();
+        config.main_clk.src = MainClkSrc::ClkIn;
+        assert_eq!(
+            config.validate_graph(),
+            Err(BrokenLink {
+                clock: Clocks::MainClk,
+                source: Clocks::ClkIn,
+                reason: ClockError::ClockNotEnabled,
+            })
+        );
+    }
+
+    #[test]
+    fn test_simulate() {
+        let summary = ClockConfig::crystal().simulate().unwrap();
+        // 48 MHz / 2 * 22 * 18 / 19
+        assert_eq!(summary.main_pll_hz, 500_210_526);
+        assert_eq!(summary.main_clk_hz, summary.main_pll_hz);
+        assert_eq!(summary.hclk_hz, summary.main_clk_hz / 2);
+        assert_eq!(summary.clk_in_hz, 0);
+
+        let mut config = ClockConfig::crystal();
+        config.main_clk.src = MainClkSrc::SFRO;
+        config.sfro.state = State::Disabled;
+        assert_eq!(config.simulate().err(), Some(ClockError::ClockNotEnabled));
+    }
+
+    #[test]
+    fn test_freq_display() {
+        extern crate std;
+        use std::string::ToString;
+
+        assert_eq!(FreqDisplay(249_999_984).to_string(), "250.00 MHz");
+        assert_eq!(FreqDisplay(48_000_000).to_string(), "48.00 MHz");
+        assert_eq!(FreqDisplay(32_768).to_string(), "32.768 kHz");
+        assert_eq!(FreqDisplay(1_000).to_string(), "1.000 kHz");
+        assert_eq!(FreqDisplay(1).to_string(), "1 Hz");
+    }
+
+    #[test]
+    fn test_i3c_fclk() {
+        assert_eq!(check_i3c_fclk(FfroFreq::Ffro48m.hz(), Divider::ONE), Ok(48_000_000));
+        assert_eq!(check_i3c_fclk(FfroFreq::Ffro48m.hz(), Divider(2)), Ok(24_000_000));
+        assert_eq!(check_i3c_fclk(500_000_000, Divider(20)), Ok(25_000_000));
+        assert_eq!(
+            check_i3c_fclk(FfroFreq::Ffro60m.hz(), Divider(2)),
+            Err(ClockError::InvalidFrequency)
+        );
+    }
+
+    #[test]
+    fn test_best_divider() {
+        assert_eq!(best_divider(SFRO_FREQ, SFRO_FREQ, 1), Ok((1, SFRO_FREQ)));
+        // 16 MHz / 3 is just above 5 MHz, so it must round up to 4
+        assert_eq!(best_divider(SFRO_FREQ, 5_000_000, u8::MAX), Ok((4, 4_000_000)));
+        assert_eq!(
+            best_divider(SFRO_FREQ, 32_000_000, u8::MAX),
+            Err(ClockError::InvalidFrequency)
+        );
+        assert_eq!(best_divider(SFRO_FREQ, 1_000, u8::MAX), Err(ClockError::InvalidDiv));
+    }
+
+    #[test]
+    fn test_pll_for_output() {
+        let ffro_div2 = FfroFreq::Ffro48m.hz() / 2;
+
+        let exact = MainPllClkConfig::for_output(ffro_div2, 528_000_000).unwrap();
+        assert_eq!(exact.achieved_hz, 528_000_000);
+        assert_eq!(exact.error_hz, 0);
+        assert_eq!(
+            MainPllClkConfig::pfd_freq(u64::from(ffro_div2) * u64::from(exact.mult), exact.pfd_div),
+            Ok(528_000_000)
+        );
+
+        let inexact = MainPllClkConfig::for_output(ffro_div2, 250_000_000).unwrap();
+        assert_eq!(inexact.achieved_hz, 246_857_142);
+        assert_eq!(inexact.error_hz, -3_142_858);
+
+        assert_eq!(
+            MainPllClkConfig::for_output(ffro_div2, 0),
+            Err(ClockError::InvalidFrequency)
+        );
+    }
+
+    #[test]
+    fn test_pll_for_outputs() {
+        let ffro_div2 = FfroFreq::Ffro48m.hz() / 2;
+
+        let plans = MainPllClkConfig::for_outputs(
+            ffro_div2,
+            [Some(528_000_000), None, Some(396_000_000), Some(316_800_000)],
+        )
+        .unwrap();
+        assert!(plans[1].is_none());
+        for plan in plans.into_iter().flatten() {
+            assert_eq!(plan.mult, 22);
+            assert_eq!(plan.error_hz, 0);
+        }
+        assert_eq!(plans[2].map(|p| p.pfd_div), Some(24));
+
+        assert_eq!(
+            MainPllClkConfig::for_outputs(ffro_div2, [None; 4]),
+            Err(ClockError::InvalidFrequency)
+        );
+    }
+
+    #[test]
+    fn test_pll_for_output_fractional() {
+        let clk_in = 19_200_000;
+
+        let integer = MainPllClkConfig::for_output(clk_in, 250_000_000).unwrap();
+        assert_eq!(integer.error_hz, 1_345_454);
+
+        let frac = MainPllClkConfig::for_output_fractional(clk_in, 250_000_000).unwrap();
+        assert_eq!(frac.achieved_hz, 250_000_000);
+        assert_eq!(frac.error_hz, 0);
+        assert_eq!(frac.denom, clk_in);
+        assert!(frac.num > 0 && frac.num < frac.denom);
+
+        // an integer setting that's already exact is kept as is
+        let exact = MainPllClkConfig::for_output_fractional(FfroFreq::Ffro48m.hz() / 2, 528_000_000).unwrap();
+        assert_eq!((exact.num, exact.denom, exact.error_hz), (0, 1, 0));
+    }
+
+    #[test]
+    fn test_fractional_mult() {
+        let ffro_div2 = FfroFreq::Ffro48m.hz() / 2;
+        assert_eq!(
+            MainPllClkConfig::check_fractional_mult(ffro_div2, 22, 0, 1),
+            Ok(528_000_000)
+        );
+        assert_eq!(
+            MainPllClkConfig::check_fractional_mult(ffro_div2, 22, 1, 2),
+            Ok(540_000_000)
+        );
+        // 561 MHz + 10.2 MHz stays just under the PLL's 572 MHz maximum
+        assert_eq!(
+            MainPllClkConfig::check_fractional_mult(25_500_000, 22, 4, 10),
+            Ok(571_200_000)
+        );
+        // each part is legal, but 561 MHz + 22.95 MHz together exceed the maximum
+        assert_eq!(
+            MainPllClkConfig::check_fractional_mult(25_500_000, 22, 9, 10),
+            Err(ClockError::InvalidFrequency)
+        );
+        assert_eq!(
+            MainPllClkConfig::check_fractional_mult(ffro_div2, 22, 1, 0),
+            Err(ClockError::InvalidMult)
+        );
+        assert_eq!(MainPllClkConfig::fractional_vco(24_000_000, 22, 1, 2), 540_000_000);
+        // a zero DENOM read back from hardware leaves the integer multiplier
+        assert_eq!(MainPllClkConfig::fractional_vco(24_000_000, 22, 1, 0), 528_000_000);
+    }
+
+    #[test]
+    fn test_pfd_div_from_multiplier() {
+        assert_eq!(PllPfd::div_from_multiplier(1, 1), Some(18));
+        assert_eq!(PllPfd::div_from_multiplier(3, 2), Some(12));
+        assert_eq!(PllPfd::div_from_multiplier(3, 4), Some(24));
+        assert_eq!(PllPfd::div_from_multiplier(1, 2), None);
+        assert_eq!(PllPfd::div_from_multiplier(2, 1), None);
+        assert_eq!(PllPfd::div_from_multiplier(0, 1), None);
+        assert_eq!(PllPfd::div_from_multiplier(1, u32::MAX), None);
+    }
+
+    #[test]
+    fn test_pll_pfd_mapping() {
+        let mut pll = ClockConfig::crystal().main_pll_clk;
+        pll.pfd0 = 18;
+        pll.pfd1 = 19;
+        pll.pfd2 = 20;
+        pll.pfd3 = 21;
+
+        assert_eq!(pll.pfd_bits(PllPfd::Pfd0), 18);
+        assert_eq!(pll.pfd_bits(PllPfd::Pfd1), 19);
+        assert_eq!(pll.pfd_bits(PllPfd::Pfd2), 20);
+        assert_eq!(pll.pfd_bits(PllPfd::Pfd3), 21);
+
+        // main_pll_clk is PFD0 of the 528 MHz VCO
+        assert_eq!(
+            MainPllClkConfig::pfd_freq(528_000_000, pll.pfd_bits(PllPfd::Pfd0)),
+            Ok(528_000_000)
+        );
+    }
+}
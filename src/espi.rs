@@ -8,7 +8,7 @@ use core::task::Poll;
 use embassy_sync::waitqueue::AtomicWaker;
 use paste::paste;
 
-use crate::clocks::{SysconPeripheral, enable_and_reset};
+use crate::clocks::{SysconPeripheral, enable_and_reset, enable_espi_fclk, record_clock_freq};
 use crate::gpio::{DriveMode, DriveStrength, Function, GpioPin as Pin, Inverter, Pull, SlewRate};
 use crate::interrupt::typelevel::Interrupt;
 pub use crate::pac::espi::espicap::{Flashmx, Maxspd, Safera, Spicap};
@@ -472,7 +472,14 @@ impl<'d> Espi<'d> {
         _io3.as_io3();
 
         // enable ESPI clock
+        let fclk = enable_espi_fclk();
+        if fclk.is_err() {
+            warn!("eSPI function clock unavailable, the FFRO is not running");
+        }
         enable_and_reset::<T>();
+        if let Ok(hz) = fclk {
+            record_clock_freq::<T>(hz);
+        }
 
         let mut instance = Espi::<'d> {
             info: T::info(),
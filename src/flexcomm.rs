@@ -4,7 +4,10 @@ use core::sync::atomic::{AtomicU8, Ordering};
 
 use paste::paste;
 
-use crate::clocks::{SysconPeripheral, disable, enable_and_reset};
+use crate::clocks::{
+    ClockConfig, ClockError, Clocks, ConfigurableClock, FfroConfig, SFRO_FREQ, SysconPeripheral, check_unlocked,
+    disable, enable_and_reset, is_clock_running, main_clk_hz, record_clock_freq,
+};
 use crate::peripherals::{
     FLEXCOMM0, FLEXCOMM1, FLEXCOMM2, FLEXCOMM3, FLEXCOMM4, FLEXCOMM5, FLEXCOMM6, FLEXCOMM7, FLEXCOMM14, FLEXCOMM15,
 };
@@ -17,6 +20,10 @@ pub enum Clock {
     Sfro,
 
     /// FFRO
+    ///
+    /// This is the full FFRO rate, 48 or 60 MHz depending on its trim. The flexcomm muxes have
+    /// no divided FFRO tap, rate tables assuming FFRO / 2 need the FRG or the peripheral's own
+    /// divider instead.
     Ffro,
 
     /// `AUDIO_PLL`
@@ -29,18 +36,42 @@ pub enum Clock {
     FcnFrgMain,
 
     /// FCn_FRG with Pll clock source
+    ///
+    /// The FRG PLL clock is the main PLL divided by `FRGPLLCLKDIV`, which is only configured by
+    /// [`crate::init`]. The main PLL must be running, so this source is unusable with a main
+    /// clock configuration that leaves the PLL powered down.
     FcnFrgPll,
 
     /// FCn_FRG with Sfro clock source
     FcnFrgSfro,
 
     /// FCn_FRG with Ffro clock source
+    ///
+    /// Like [`Clock::Ffro`], the FRG sees the full 48 or 60 MHz FFRO rate.
     FcnFrgFfro,
 
     /// disabled
+    ///
+    /// A flexcomm can't run without a function clock, so every driver rejects this with an error.
     None,
 }
 
+impl TryFrom<Clocks> for Clock {
+    type Error = ClockError;
+
+    /// Maps a clock source to the flexcomm function clock selection fed by it.
+    ///
+    /// The main clock is only reachable through the FRG, so it maps to [`Clock::FcnFrgMain`].
+    fn try_from(clock: Clocks) -> Result<Self, Self::Error> {
+        match clock {
+            Clocks::Sfro => Ok(Clock::Sfro),
+            Clocks::Ffro => Ok(Clock::Ffro),
+            Clocks::MainClk => Ok(Clock::FcnFrgMain),
+            _ => Err(ClockError::ClockNotSupported),
+        }
+    }
+}
+
 /// do not allow implementation of trait outside this mod
 mod sealed {
     /// trait does not get re-exported outside flexcomm mod, allowing us to safely expose only desired APIs
@@ -98,13 +129,62 @@ impl Drop for FlexcommRef {
     }
 }
 
+/// Sanity checks the function clock a flexcomm is about to be enabled with.
+///
+/// [`Clock::None`] gates the function clock entirely, leaving a peripheral that accepts
+/// configuration but never shifts a bit, so it's rejected with [`ClockError::ClockNotSupported`]
+/// for the driver to pass on.
+fn check_source(clk: Clock) -> Result<(), ClockError> {
+    if matches!(clk, Clock::None) {
+        return Err(ClockError::ClockNotSupported);
+    }
+    check_frg_pll(clk);
+    Ok(())
+}
+
+/// Checks that the FRG PLL clock is available before a flexcomm is switched to it.
+///
+/// Selecting [`Clock::FcnFrgPll`] while the main PLL is down or `FRGPLLCLKDIV` is halted leaves
+/// the flexcomm without a function clock, which otherwise only shows up as a stalled transfer.
+fn check_frg_pll(clk: Clock) {
+    if !matches!(clk, Clock::FcnFrgPll) {
+        return;
+    }
+
+    if !frg_pll_running() {
+        warn!("flexcomm FRG PLL source selected, but the FRG PLL clock is not running");
+    }
+}
+
+/// Returns whether the main PLL is running and `FRGPLLCLKDIV` passes it on to the FRGs
+fn frg_pll_running() -> bool {
+    // SAFETY: unsafe needed to take pointer to Clkctl1, only used for a register read
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+    is_clock_running(Clocks::MainPllClk) && clkctl1.frgpllclkdiv().read().halt().bit_is_clear()
+}
+
+/// Returns the function clock `clk` gives a freshly enabled flexcomm, `None` if it isn't known.
+///
+/// Enabling resets the FRG multiplier to 0, so the FRG sources pass their input through as is.
+/// The audio PLL and MCLK aren't set up by this crate, and the FRG PLL rate depends on a
+/// [`ClockConfig`] the flexcomm enable path doesn't have.
+fn enabled_fclk(clk: Clock) -> Option<u32> {
+    match clk {
+        Clock::Sfro | Clock::FcnFrgSfro => is_clock_running(Clocks::Sfro).then_some(SFRO_FREQ),
+        Clock::Ffro | Clock::FcnFrgFfro => is_clock_running(Clocks::Ffro).then(|| FfroConfig::trimmed_freq().hz()),
+        Clock::FcnFrgMain => Some(main_clk_hz()).filter(|&hz| hz != 0),
+        Clock::FcnFrgPll | Clock::AudioPll | Clock::Master | Clock::None => None,
+    }
+}
+
 /// primary low-level flexcomm interface
 pub(crate) trait FlexcommLowLevel: sealed::Sealed + PeripheralType + SysconPeripheral + 'static + Send {
     // fetch the flexcomm register block for direct manipulation
     fn reg() -> &'static pac::flexcomm0::RegisterBlock;
 
-    // set the clock select for this flexcomm instance and remove from reset
-    fn enable(clk: Clock) -> FlexcommRef;
+    // set the clock select for this flexcomm instance and remove from reset,
+    // fails without touching the hardware if `clk` can't clock the flexcomm
+    fn enable(clk: Clock) -> Result<FlexcommRef, ClockError>;
 
     // deconfigure the clock select
     fn disable();
@@ -129,7 +209,9 @@ macro_rules! impl_flexcomm {
                         }
                     }
 
-                    fn enable(clk: Clock) -> FlexcommRef {
+                    fn enable(clk: Clock) -> Result<FlexcommRef, ClockError> {
+                        check_source(clk)?;
+
                         // SAFETY: safe from single executor
                         let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
 
@@ -142,7 +224,7 @@ macro_rules! impl_flexcomm {
                             Clock::FcnFrgPll => w.sel().fcn_frg_clk(),
                             Clock::FcnFrgSfro => w.sel().fcn_frg_clk(),
                             Clock::FcnFrgFfro => w.sel().fcn_frg_clk(),
-                            Clock::None => w.sel().none(), // no clock? throw an error?
+                            Clock::None => w.sel().none(), // rejected by check_source above
                         });
 
                         clkctl1.flexcomm($idx).frgclksel().write(|w| match clk {
@@ -162,8 +244,11 @@ macro_rules! impl_flexcomm {
                             unsafe { w.mult().bits(0) });
 
                         enable_and_reset::<[<FLEXCOMM $idx>]>();
+                        if let Some(hz) = enabled_fclk(clk) {
+                            record_clock_freq::<Self>(hz);
+                        }
 
-                        FlexcommRef::new::<Self>()
+                        Ok(FlexcommRef::new::<Self>())
                     }
 
                     fn disable() {
@@ -198,7 +283,9 @@ impl FlexcommLowLevel for crate::peripherals::FLEXCOMM14 {
         unsafe { &*crate::pac::Flexcomm14::ptr() }
     }
 
-    fn enable(clk: Clock) -> FlexcommRef {
+    fn enable(clk: Clock) -> Result<FlexcommRef, ClockError> {
+        check_source(clk)?;
+
         // SAFETY: safe from single executor
         let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
 
@@ -211,7 +298,7 @@ impl FlexcommLowLevel for crate::peripherals::FLEXCOMM14 {
             Clock::FcnFrgPll => w.sel().fcn_frg_clk(),
             Clock::FcnFrgSfro => w.sel().fcn_frg_clk(),
             Clock::FcnFrgFfro => w.sel().fcn_frg_clk(),
-            Clock::None => w.sel().none(), // no clock? throw an error?
+            Clock::None => w.sel().none(), // rejected by check_source above
         });
 
         clkctl1.frg14clksel().write(|w| match clk {
@@ -228,8 +315,11 @@ impl FlexcommLowLevel for crate::peripherals::FLEXCOMM14 {
                 unsafe { w.mult().bits(0) });
 
         enable_and_reset::<FLEXCOMM14>();
+        if let Some(hz) = enabled_fclk(clk) {
+            record_clock_freq::<Self>(hz);
+        }
 
-        FlexcommRef::new::<Self>()
+        Ok(FlexcommRef::new::<Self>())
     }
 
     fn disable() {
@@ -257,7 +347,9 @@ impl FlexcommLowLevel for crate::peripherals::FLEXCOMM15 {
         unsafe { &*crate::pac::Flexcomm15::ptr() }
     }
 
-    fn enable(clk: Clock) -> FlexcommRef {
+    fn enable(clk: Clock) -> Result<FlexcommRef, ClockError> {
+        check_source(clk)?;
+
         // SAFETY: safe from single executor
         let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
 
@@ -270,7 +362,7 @@ impl FlexcommLowLevel for crate::peripherals::FLEXCOMM15 {
             Clock::FcnFrgPll => w.sel().fcn_frg_clk(),
             Clock::FcnFrgSfro => w.sel().fcn_frg_clk(),
             Clock::FcnFrgFfro => w.sel().fcn_frg_clk(),
-            Clock::None => w.sel().none(), // no clock? throw an error?
+            Clock::None => w.sel().none(), // rejected by check_source above
         });
         clkctl1.frg15clksel().write(|w| match clk {
             Clock::FcnFrgMain => w.sel().main_clk(),
@@ -285,8 +377,11 @@ impl FlexcommLowLevel for crate::peripherals::FLEXCOMM15 {
                 unsafe { w.mult().bits(0) });
 
         enable_and_reset::<FLEXCOMM15>();
+        if let Some(hz) = enabled_fclk(clk) {
+            record_clock_freq::<Self>(hz);
+        }
 
-        FlexcommRef::new::<Self>()
+        Ok(FlexcommRef::new::<Self>())
     }
 
     fn disable() {
@@ -304,6 +399,181 @@ impl FlexcommLowLevel for crate::peripherals::FLEXCOMM15 {
     }
 }
 
+/// Reprograms the FRG multiplier of flexcomm `fc` (0-7, 14 or 15) at runtime.
+///
+/// The FRG divides its source by `1 + mult / 256`, giving much finer steps than the flexcomm's
+/// own integer dividers. Returns the resulting function clock, computed from the FRG source
+/// currently selected in hardware.
+///
+/// Every `mult` is valid, but the usable range is only a factor of two: 255 gives roughly
+/// `source / 2`, so any lower rate needs the peripheral's own divider. Representative values
+/// for a 48 MHz source:
+///
+/// | `mult` | `FOUT / source` | `FOUT` at 48 MHz |
+/// |-------:|----------------:|-----------------:|
+/// | 0      | 1               | 48.000 MHz       |
+/// | 32     | 256 / 288       | 42.667 MHz       |
+/// | 64     | 256 / 320       | 38.400 MHz       |
+/// | 128    | 256 / 384       | 32.000 MHz       |
+/// | 192    | 256 / 448       | 27.429 MHz       |
+/// | 255    | 256 / 511       | 24.047 MHz       |
+///
+/// Errors with [`ClockError::ClockMismatch`] if the flexcomm function clock isn't fed by its FRG,
+/// and [`ClockError::ClockNotEnabled`] if the FRG has no source selected.
+pub fn set_frg_mult(fc: usize, mult: u8, config: &ClockConfig) -> Result<u32, ClockError> {
+    check_unlocked()?;
+    // SAFETY: unsafe needed to take pointer to Clkctl1, only to update the FRG of `fc`
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+
+    macro_rules! frg_src_rate {
+        ($sel:expr) => {{
+            let sel = $sel;
+            if sel.is_main_clk() {
+                config.main_clk.ensure_main_clk()
+            } else if sel.is_frg_pll_clk() {
+                let div = clkctl1.frgpllclkdiv().read().div().bits() as u32 + 1;
+                Ok(config.main_pll_clk.get_clock_rate()? / div)
+            } else if sel.is_sfro_clk() {
+                config.sfro.get_clock_rate()
+            } else if sel.is_ffro_clk() {
+                config.ffro.get_clock_rate()
+            } else {
+                Err(ClockError::ClockNotEnabled)
+            }
+        }};
+    }
+
+    // The source rate is resolved before `mult` is written, so an error leaves the FRG untouched.
+    // SAFETY: unsafe only used for .bits() call, every mult value is valid
+    let src_rate = match fc {
+        0..=7 => {
+            let regs = clkctl1.flexcomm(fc);
+            if !regs.fcfclksel().read().sel().is_fcn_frg_clk() {
+                return Err(ClockError::ClockMismatch);
+            }
+            let rate = frg_src_rate!(regs.frgclksel().read().sel())?;
+            regs.frgctl().modify(|_, w| unsafe { w.mult().bits(mult) });
+            rate
+        }
+        14 => {
+            if !clkctl1.fc14fclksel().read().sel().is_fcn_frg_clk() {
+                return Err(ClockError::ClockMismatch);
+            }
+            let rate = frg_src_rate!(clkctl1.frg14clksel().read().sel())?;
+            clkctl1.frg14ctl().modify(|_, w| unsafe { w.mult().bits(mult) });
+            rate
+        }
+        15 => {
+            if !clkctl1.fc15fclksel().read().sel().is_fcn_frg_clk() {
+                return Err(ClockError::ClockMismatch);
+            }
+            let rate = frg_src_rate!(clkctl1.frg15clksel().read().sel())?;
+            clkctl1.frg15ctl().modify(|_, w| unsafe { w.mult().bits(mult) });
+            rate
+        }
+        _ => return Err(ClockError::ClockNotSupported),
+    };
+
+    Ok(frg_output(src_rate, mult))
+}
+
+/// FRG output = src / (1 + mult / 256), with div fixed at 0xFF
+fn frg_output(src_rate: u32, mult: u8) -> u32 {
+    (u64::from(src_rate) * 256 / (256 + u64::from(mult))) as u32
+}
+
+/// Retunes FLEXCOMM14 (HS SPI) and FLEXCOMM15 (PMIC I2C) from one shared FRG source.
+///
+/// Boards usually bring these two up together at related rates. Both FRGs are switched to
+/// `src`, which must be one of the `FcnFrg*` sources, and given their own multiplier (see
+/// [`set_frg_mult`]). Returns the FLEXCOMM14 and FLEXCOMM15 function clocks, both computed from
+/// the rate of `src` before anything is written.
+///
+/// Everything is checked before any register is touched: [`ClockError::ClockNotSupported`]
+/// for a non-FRG `src`, [`ClockError::ClockNotRunning`] if `src` isn't running, and
+/// [`ClockError::ClockMismatch`] if either flexcomm isn't fed by its FRG.
+pub fn set_fc14_fc15_frg(src: Clock, mult14: u8, mult15: u8, config: &ClockConfig) -> Result<(u32, u32), ClockError> {
+    check_unlocked()?;
+    if !matches!(
+        src,
+        Clock::FcnFrgMain | Clock::FcnFrgPll | Clock::FcnFrgSfro | Clock::FcnFrgFfro
+    ) {
+        return Err(ClockError::ClockNotSupported);
+    }
+    let src_rate = source_rate(src, config).ok_or(ClockError::ClockNotRunning)?;
+
+    // SAFETY: unsafe needed to take pointer to Clkctl1, only to update the FRGs of FLEXCOMM14/15
+    let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+    if !clkctl1.fc14fclksel().read().sel().is_fcn_frg_clk() || !clkctl1.fc15fclksel().read().sel().is_fcn_frg_clk() {
+        return Err(ClockError::ClockMismatch);
+    }
+
+    clkctl1.frg14clksel().write(|w| match src {
+        Clock::FcnFrgMain => w.sel().main_clk(),
+        Clock::FcnFrgPll => w.sel().frg_pll_clk(),
+        Clock::FcnFrgSfro => w.sel().sfro_clk(),
+        _ => w.sel().ffro_clk(),
+    });
+    clkctl1.frg15clksel().write(|w| match src {
+        Clock::FcnFrgMain => w.sel().main_clk(),
+        Clock::FcnFrgPll => w.sel().frg_pll_clk(),
+        Clock::FcnFrgSfro => w.sel().sfro_clk(),
+        _ => w.sel().ffro_clk(),
+    });
+    // SAFETY: unsafe only used for .bits() call, every mult value is valid
+    clkctl1.frg14ctl().modify(|_, w| unsafe { w.mult().bits(mult14) });
+    clkctl1.frg15ctl().modify(|_, w| unsafe { w.mult().bits(mult15) });
+
+    Ok((frg_output(src_rate, mult14), frg_output(src_rate, mult15)))
+}
+
+/// Function clock sources considered by [`source_options`]
+const SOURCE_CANDIDATES: [Clock; 6] = [
+    Clock::Sfro,
+    Clock::Ffro,
+    Clock::FcnFrgMain,
+    Clock::FcnFrgPll,
+    Clock::FcnFrgSfro,
+    Clock::FcnFrgFfro,
+];
+
+/// Lists the flexcomm function clock sources that are currently available, with their rates.
+///
+/// Sources that aren't running are skipped. FRG sources are listed at their undivided rate,
+/// the FRG multiplier can only lower it from there (see [`set_frg_mult`]). [`Clock::AudioPll`]
+/// and [`Clock::Master`] aren't set up by this crate and are never listed. A driver can use
+/// this to pick the source best suited to its target rate, e.g. a UART baud rate.
+pub fn source_options(config: &ClockConfig) -> impl Iterator<Item = (Clock, u32)> + '_ {
+    SOURCE_CANDIDATES
+        .into_iter()
+        .filter_map(move |clk| source_rate(clk, config).map(|hz| (clk, hz)))
+}
+
+/// Returns the rate `clk` feeds the flexcomm (or its FRG) with, `None` if it isn't running
+fn source_rate(clk: Clock, config: &ClockConfig) -> Option<u32> {
+    match clk {
+        Clock::Sfro | Clock::FcnFrgSfro => {
+            if is_clock_running(Clocks::Sfro) {
+                config.sfro.get_clock_rate().ok()
+            } else {
+                None
+            }
+        }
+        Clock::Ffro | Clock::FcnFrgFfro => config.ffro.get_clock_rate().ok(),
+        Clock::FcnFrgMain => config.main_clk.ensure_main_clk().ok(),
+        Clock::FcnFrgPll => {
+            if !frg_pll_running() {
+                return None;
+            }
+            // SAFETY: unsafe needed to take pointer to Clkctl1, only used for a register read
+            let clkctl1 = unsafe { crate::pac::Clkctl1::steal() };
+            let div = u32::from(clkctl1.frgpllclkdiv().read().div().bits()) + 1;
+            config.main_pll_clk.get_clock_rate().ok().map(|hz| hz / div)
+        }
+        Clock::AudioPll | Clock::Master | Clock::None => None,
+    }
+}
+
 macro_rules! into_mode {
     ($mode:ident, $($fc:ident),*) => {
         paste! {
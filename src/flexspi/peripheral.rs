@@ -1,5 +1,6 @@
 //! Low level FlexSPI peripheral access.
 
+use crate::clocks::{ClockConfig, ClockError, Divider, FlexspiClkSrc, check_unlocked};
 use crate::peripherals::FLEXSPI;
 use crate::{Peri, pac};
 
@@ -41,6 +42,27 @@ mod arm {
 
     /// The bitmask of the SEQTIMEOUT (sequence timeout) interrupt in the INTR register.
     pub const SEQTIMEOUT: u32 = 1 << 11;
+
+    /// The byte offset of the FlexSPI MCR0 register.
+    pub const FLEXSPI_MCR0: u16 = 0x00;
+
+    /// The byte offset of the FlexSPI STS0 register.
+    pub const FLEXSPI_STS0: u16 = 0xE0;
+
+    /// The bitmask of the SWRESET (software reset) bit in the MCR0 register.
+    pub const MCR0_SWRESET: u32 = 1 << 0;
+
+    /// The bitmask of the MDIS (module disable) bit in the MCR0 register.
+    pub const MCR0_MDIS: u32 = 1 << 1;
+
+    /// The bitmask of the SEQIDLE and ARBIDLE bits in the STS0 register.
+    pub const STS0_IDLE: u32 = 0b11;
+
+    /// The bitmask of the REQFLAG (divider change in progress) bit in the FLEXSPIFCLKDIV register.
+    pub const FCLKDIV_REQFLAG: u32 = 1 << 31;
+
+    /// The bitmask of the RESET (divider counter reset) bit in the FLEXSPIFCLKDIV register.
+    pub const FCLKDIV_RESET: u32 = 1 << 29;
 }
 
 #[cfg(target_arch = "arm")]
@@ -118,6 +140,118 @@ impl<'a> FlexSpi<'a> {
         flexspi.lutcr().write(|w| w.lock().set_bit());
     }
 
+    /// Switch the FlexSPI function clock to `source` divided by `div`, returning the new rate.
+    ///
+    /// The FlexSPI clock feeds execute-in-place flash, so it can't be changed from code that runs
+    /// from flash: the core would fetch instructions while the controller is disabled and hang.
+    /// The switch itself is located in RAM (the .data section) and implemented in inline assembly.
+    /// It waits for the controller to go idle, disables it, reprograms the mux and divider,
+    /// then re-enables and soft-resets it. Interrupts are disabled for the whole sequence.
+    ///
+    /// Errors without touching the clock if `source` isn't running.
+    ///
+    /// # Safety
+    /// The new rate must be within what the connected flash and the FlexSPI timing setup
+    /// (e.g. the FCB read sequence and sampling) support, otherwise memory mapped flash access
+    /// breaks after the switch.
+    ///
+    /// No IP command may currently be running on the FlexSPI peripheral.
+    ///
+    /// You must also ensure that the .data section is executable before calling this function.
+    pub unsafe fn reconfigure_clock(
+        &mut self,
+        source: FlexspiClkSrc,
+        div: Divider,
+        config: &ClockConfig,
+    ) -> Result<u32, ClockError> {
+        check_unlocked()?;
+        let rate = div.divide(source.rate(config)?);
+
+        // SAFETY: only used to look up the register addresses for the RAM routine
+        let clkctl0 = unsafe { pac::Clkctl0::steal() };
+        let sel = clkctl0.flexspifclksel().as_ptr();
+        let fclkdiv = clkctl0.flexspifclkdiv().as_ptr();
+
+        critical_section::with(|_| unsafe {
+            Self::_reconfigure_clock(
+                sel,
+                fclkdiv,
+                u32::from(u8::from(source.sel())),
+                u32::from(div.reg_bits()),
+            )
+        });
+        Ok(rate)
+    }
+
+    /// Implementation details for [`Self::reconfigure_clock()`].
+    ///
+    /// This part is located in RAM (the .data section) and implemented in inline assembly,
+    /// to ensure that no instructions need to be fetched from FLASH while the FlexSPI peripheral is disabled.
+    #[unsafe(link_section = ".data")]
+    #[inline(never)]
+    unsafe fn _reconfigure_clock(sel: *mut u32, fclkdiv: *mut u32, sel_bits: u32, div_bits: u32) {
+        #[cfg(not(target_arch = "arm"))]
+        {
+            let _ = (sel, fclkdiv, sel_bits, div_bits);
+        }
+        #[cfg(target_arch = "arm")]
+        {
+            unsafe {
+                core::arch::asm! {
+                    // Wait for the controller to be idle.
+                    "2:",
+                        "ldr {value}, [{flexspi_base}, #{FLEXSPI_STS0}]",
+                        "and {value}, {value}, #{STS0_IDLE}",
+                        "cmp {value}, #{STS0_IDLE}",
+                        "bne 2b",
+
+                    // Disable the controller.
+                    "ldr {value}, [{flexspi_base}, #{FLEXSPI_MCR0}]",
+                    "orr {value}, {value}, #{MCR0_MDIS}",
+                    "str {value}, [{flexspi_base}, #{FLEXSPI_MCR0}]",
+
+                    // Switch the clock, then reset the divider and write the new value. `div_bits`
+                    // only holds DIV, so the same write also clears HALT and RESET. Wait for the
+                    // divider to take the new value before the controller runs from it again.
+                    "str {sel_bits}, [{sel}]",
+                    "ldr {value}, [{fclkdiv}]",
+                    "orr {value}, {value}, #{FCLKDIV_RESET}",
+                    "str {value}, [{fclkdiv}]",
+                    "str {div_bits}, [{fclkdiv}]",
+                    "3:",
+                        "ldr {value}, [{fclkdiv}]",
+                        "tst {value}, #{FCLKDIV_REQFLAG}",
+                        "bne 3b",
+
+                    // Enable the controller again and soft-reset it to resync to the new clock.
+                    "ldr {value}, [{flexspi_base}, #{FLEXSPI_MCR0}]",
+                    "bic {value}, {value}, #{MCR0_MDIS}",
+                    "orr {value}, {value}, #{MCR0_SWRESET}",
+                    "str {value}, [{flexspi_base}, #{FLEXSPI_MCR0}]",
+                    "4:",
+                        "ldr {value}, [{flexspi_base}, #{FLEXSPI_MCR0}]",
+                        "tst {value}, #{MCR0_SWRESET}",
+                        "bne 4b",
+
+                    flexspi_base = in(reg) FLEXSPI_BASE,
+                    FLEXSPI_MCR0 = const FLEXSPI_MCR0,
+                    FLEXSPI_STS0 = const FLEXSPI_STS0,
+                    MCR0_SWRESET = const MCR0_SWRESET,
+                    MCR0_MDIS = const MCR0_MDIS,
+                    STS0_IDLE = const STS0_IDLE,
+                    FCLKDIV_REQFLAG = const FCLKDIV_REQFLAG,
+                    FCLKDIV_RESET = const FCLKDIV_RESET,
+                    sel = in(reg) sel,
+                    fclkdiv = in(reg) fclkdiv,
+                    sel_bits = in(reg) sel_bits,
+                    div_bits = in(reg) div_bits,
+                    value = out(reg) _,
+                    options(nostack),
+                }
+            }
+        }
+    }
+
     /// Read a LUT sequence by index.
     ///
     /// The index should be in the range 0..32.
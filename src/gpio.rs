@@ -10,7 +10,7 @@ use embassy_hal_internal::interrupt::InterruptExt;
 use embassy_sync::waitqueue::AtomicWaker;
 use sealed::Sealed;
 
-use crate::clocks::enable_and_reset;
+use crate::clocks::{ClockError, enable_and_reset, hclk_hz};
 use crate::iopctl::IopctlPin;
 pub use crate::iopctl::{AnyPin, DriveMode, DriveStrength, Function, Inverter, Pull, SlewRate};
 use crate::{Peri, PeripheralType, interrupt, peripherals};
@@ -105,6 +105,18 @@ fn irq_handler(port_wakers: &[Option<&PortWaker>]) {
     }
 }
 
+/// Clock rate of the HSGPIO banks.
+///
+/// The banks have no function clock of their own and are clocked from the AHB clock (hclk),
+/// so this is the rate to use for e.g. input filter or debounce timing. Errors with
+/// [`ClockError::ClockNotRunning`] before the clocks are initialized.
+pub fn bank_clock_rate() -> Result<u32, ClockError> {
+    match hclk_hz() {
+        0 => Err(ClockError::ClockNotRunning),
+        hz => Ok(hz),
+    }
+}
+
 /// Initialization Logic
 /// Note: GPIO port clocks are initialized in the clocks module.
 pub(crate) fn init() {
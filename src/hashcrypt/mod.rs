@@ -5,7 +5,7 @@ use embassy_hal_internal::PeripheralType;
 use embassy_sync::waitqueue::AtomicWaker;
 use hasher::Hasher;
 
-use crate::clocks::enable_and_reset;
+use crate::clocks::{ClockError, enable_and_reset, hclk_hz, record_clock_freq};
 use crate::peripherals::{DMA0_CH30, HASHCRYPT};
 use crate::{Peri, dma, interrupt, pac};
 
@@ -100,6 +100,7 @@ impl<'d, M: Mode> Hashcrypt<'d, M> {
     /// Instantiate new Hashcrypt peripheral
     fn new_inner<T: Instance>(_peripheral: Peri<'d, T>, dma_ch: Option<dma::channel::Channel<'d>>) -> Self {
         enable_and_reset::<HASHCRYPT>();
+        record_clock_freq::<HASHCRYPT>(hclk_hz());
 
         Self {
             _ownership: PhantomData,
@@ -109,6 +110,28 @@ impl<'d, M: Mode> Hashcrypt<'d, M> {
         }
     }
 
+    /// Clock rate of the Hashcrypt engine in Hz, e.g. to estimate hashing throughput.
+    ///
+    /// Hashcrypt has no function clock of its own and runs from the AHB clock. It also has no
+    /// dedicated SRAM to power up, so it is usable as soon as its bus clock is enabled.
+    #[must_use]
+    pub fn clock_rate(&self) -> u32 {
+        hclk_hz()
+    }
+
+    /// Checks that the AHB clock is at least `min_hz`, returning the current rate.
+    ///
+    /// Hashing throughput scales with hclk, so a driver with a throughput budget can refuse to
+    /// run when the AHB divider was raised for power saving. Errors with
+    /// [`ClockError::ClockNotRunning`] before the clocks are initialized.
+    pub fn check_clock_rate(&self, min_hz: u32) -> Result<u32, ClockError> {
+        match hclk_hz() {
+            0 => Err(ClockError::ClockNotRunning),
+            hz if hz < min_hz => Err(ClockError::InvalidFrequency),
+            hz => Ok(hz),
+        }
+    }
+
     // Safety: unsafe for writing algorithm type to register
     fn start_algorithm(&mut self, algorithm: Algorithm, dma: bool) {
         self.hashcrypt.ctrl().write(|w| w.mode().disabled().new_hash().start());
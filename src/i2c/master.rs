@@ -242,7 +242,7 @@ impl<'a, M: Mode> I2cMaster<'a, M> {
     ) -> Result<Self> {
         // TODO - clock integration
         let clock = crate::flexcomm::Clock::Ffro;
-        let flexcomm = T::enable(clock);
+        let flexcomm = T::enable(clock).map_err(|_| Error::UnsupportedConfiguration)?;
         T::into_i2c();
 
         sda.as_sda();
@@ -9,8 +9,8 @@ use embassy_hal_internal::Peri;
 use embassy_hal_internal::drop::OnDrop;
 
 use super::{
-    Async, Blocking, I2C_REMEDIATION, I2C_WAKERS, Info, Instance, InterruptHandler, Mode, REMEDIATON_SLAVE_NAK, Result,
-    SclPin, SdaPin, SlaveDma, TEN_BIT_PREFIX, TransferError,
+    Async, Blocking, Error, I2C_REMEDIATION, I2C_WAKERS, Info, Instance, InterruptHandler, Mode, REMEDIATON_SLAVE_NAK,
+    Result, SclPin, SdaPin, SlaveDma, TEN_BIT_PREFIX, TransferError,
 };
 use crate::flexcomm::FlexcommRef;
 use crate::interrupt::typelevel::Interrupt;
@@ -150,7 +150,7 @@ impl<'a, M: Mode> I2cSlave<'a, M> {
     ) -> Result<Self> {
         // TODO - clock integration
         let clock = crate::flexcomm::Clock::Sfro;
-        let flexcomm = T::enable(clock);
+        let flexcomm = T::enable(clock).map_err(|_| Error::UnsupportedConfiguration)?;
         T::into_i2c();
 
         sda.as_sda();
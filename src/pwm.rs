@@ -34,7 +34,18 @@ use crate::Peri;
 /// include pac definitions for instancing
 use crate::pac;
 
+/// SCT input fed by the divided `SCTFCLKSEL` function clock.
+///
+/// The function clock reaches the `SCTimer` as input 7, so it only clocks the counter when
+/// `CONFIG.CLKMODE` selects input clock mode with `CKSEL` set to input 7; in system clock mode
+/// the counter runs from the bus clock instead.
+pub const SCT_FCLK_INPUT: u8 = 7;
+
 /// clock source indicator for selecting while powering on the `SCTimer`
+///
+/// The `SCTFCLKSEL` mux cannot select the 16m_irc (SFRO) directly. To clock the `SCTimer`
+/// from the SFRO, select it as the main clock source and use [`SCTClockSource::Main`].
+/// The selected clock is divided by `SCTFCLKDIV` and feeds SCT input [`SCT_FCLK_INPUT`].
 #[derive(Copy, Clone, Debug)]
 pub enum SCTClockSource {
     /// main clock
@@ -59,6 +70,20 @@ pub enum SCTClockSource {
     None,
 }
 
+impl TryFrom<Clocks> for SCTClockSource {
+    type Error = ClockError;
+
+    /// Maps a clock source to the `SCTFCLKSEL` selection fed by it.
+    fn try_from(clock: Clocks) -> Result<Self, Self::Error> {
+        match clock {
+            Clocks::MainClk => Ok(SCTClockSource::Main),
+            Clocks::MainPllClk => Ok(SCTClockSource::MainPLL),
+            Clocks::Ffro => Ok(SCTClockSource::FFRO),
+            _ => Err(ClockError::ClockNotSupported),
+        }
+    }
+}
+
 /// `SCTimer` based PWM Interface Constraints
 #[derive(Copy, Clone, Debug)]
 pub enum Channel {
@@ -244,11 +269,8 @@ impl sealed::SCTimer for crate::peripherals::SCT0 {
         // SAFETY: safe so long as executed from single executor context or during initialization only
         let clkctl0 = unsafe { pac::Clkctl0::steal() };
 
-        clkctl0.sctfclkdiv().modify(|_, w| w.halt().set_bit().reset().set_bit());
-        clkctl0.sctfclkdiv().modify(|_, w|
-                // SAFETY: safe as long as the above is still true
-                unsafe { w.div().bits(div) });
-        clkctl0.sctfclkdiv().modify(|_, w| w.halt().clear_bit());
+        // waits for REQFLAG, so the SCT never counts from a divider that hasn't latched yet
+        clkctl0.sctfclkdiv().set_div(Divider::from_reg_bits(div));
     }
 
     fn configure(base_period: u32) {
@@ -363,7 +385,7 @@ impl<T: sealed::SCTimer> Drop for SCTPwm<'_, T> {
 
 pub use embedded_hal_02::Pwm;
 
-use crate::clocks::enable_and_reset;
+use crate::clocks::{ClkDivider, ClockError, Clocks, Divider, enable_and_reset};
 use crate::peripherals::SCT0;
 
 impl<T: sealed::SCTimer> embedded_hal_02::Pwm for SCTPwm<'_, T> {
@@ -36,7 +36,8 @@ impl IoMode for Async {}
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum Error {
-    // No errors for now.
+    /// The function clock the configuration needs can't clock the flexcomm
+    UnsupportedConfiguration,
 }
 
 /// Spi driver.
@@ -54,7 +55,7 @@ impl<'a> Spi<'a, Blocking> {
         mosi: Peri<'a, impl MosiPin<T> + 'a>,
         miso: Peri<'a, impl MisoPin<T> + 'a>,
         config: Config,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         sck.as_sck();
         mosi.as_mosi();
         miso.as_miso();
@@ -68,7 +69,7 @@ impl<'a> Spi<'a, Blocking> {
         sck: Peri<'a, impl SckPin<T> + 'a>,
         mosi: Peri<'a, impl MosiPin<T> + 'a>,
         config: Config,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         sck.as_sck();
         mosi.as_mosi();
 
@@ -81,7 +82,7 @@ impl<'a> Spi<'a, Blocking> {
         sck: Peri<'a, impl SckPin<T> + 'a>,
         miso: Peri<'a, impl MisoPin<T> + 'a>,
         config: Config,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         sck.as_sck();
         miso.as_miso();
 
@@ -92,7 +93,7 @@ impl<'a> Spi<'a, Blocking> {
     ///
     /// WARNING: This is only useful for testing as it doesn't use any
     /// external pins.
-    pub fn new_blocking_loopback<T: Instance>(_inner: Peri<'a, T>, config: Config) -> Self {
+    pub fn new_blocking_loopback<T: Instance>(_inner: Peri<'a, T>, config: Config) -> Result<Self, Error> {
         Self::new_inner(_inner, None, None, None, config)
     }
 }
@@ -233,7 +234,7 @@ impl<'a> Spi<'a, Async> {
         miso: Peri<'a, impl MisoPin<T> + 'a>,
         _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'a,
         config: Config,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         sck.as_sck();
         mosi.as_mosi();
         miso.as_miso();
@@ -251,7 +252,7 @@ impl<'a> Spi<'a, Async> {
         mosi: Peri<'a, impl MosiPin<T> + 'a>,
         _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'a,
         config: Config,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         sck.as_sck();
         mosi.as_mosi();
 
@@ -268,7 +269,7 @@ impl<'a> Spi<'a, Async> {
         miso: Peri<'a, impl MisoPin<T> + 'a>,
         _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'a,
         config: Config,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         sck.as_sck();
         miso.as_miso();
 
@@ -286,7 +287,7 @@ impl<'a> Spi<'a, Async> {
         _inner: Peri<'a, T>,
         _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'a,
         config: Config,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         T::Interrupt::unpend();
         unsafe { T::Interrupt::enable() };
 
@@ -558,11 +559,11 @@ impl<'a, M: IoMode> Spi<'a, M> {
         mosi: Option<Peri<'a, AnyPin>>,
         miso: Option<Peri<'a, AnyPin>>,
         config: Config,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         // REVISIT: allow selecting from multiple clocks.
         let clk = Self::clock(&config);
 
-        let flexcomm = T::enable(clk);
+        let flexcomm = T::enable(clk).map_err(|_| Error::UnsupportedConfiguration)?;
         T::into_spi();
 
         Self::apply_config(T::info().regs, &config);
@@ -623,11 +624,11 @@ impl<'a, M: IoMode> Spi<'a, M> {
             _ => {}
         });
 
-        Self {
+        Ok(Self {
             info,
             _flexcomm: flexcomm,
             _phantom: PhantomData,
-        }
+        })
     }
 
     fn set_config(&mut self, config: &Config) {
@@ -948,7 +949,9 @@ impl<'d, M: IoMode> embedded_hal_02::blocking::spi::Write<u8> for Spi<'d, M> {
 
 impl embedded_hal_1::spi::Error for Error {
     fn kind(&self) -> embedded_hal_1::spi::ErrorKind {
-        match *self {}
+        match *self {
+            Self::UnsupportedConfiguration => embedded_hal_1::spi::ErrorKind::Other,
+        }
     }
 }
 
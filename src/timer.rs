@@ -6,7 +6,7 @@ use core::task::Poll;
 use embassy_sync::waitqueue::AtomicWaker;
 use paste::paste;
 
-use crate::clocks::{ClockConfig, ConfigurableClock, enable_and_reset};
+use crate::clocks::{ClockConfig, Clocks, ConfigurableClock, check_unlocked, enable_and_reset, is_clock_running};
 use crate::interrupt::typelevel::Interrupt;
 use crate::iopctl::{DriveMode, DriveStrength, Inverter, IopctlPin as Pin, Pull, SlewRate};
 use crate::pac::Clkctl1;
@@ -45,6 +45,18 @@ pub enum Error {
 
     /// Pwm length channel and output channel does not belong to same CTimer
     PwmChannelMismatch,
+
+    /// The clock source selected for the CTimer is powered down or gated
+    ClockSourceDisabled,
+
+    /// Requested tick rate cannot be derived from the CTimer clock source
+    InvalidTickRate,
+
+    /// Invalid argument, e.g. a `CTimer` module or clock source that doesn't exist
+    InvalidArgument,
+
+    /// Clock tree locked by [`crate::clocks::lock_clocks`]
+    ClocksLocked,
 }
 
 /// Enum representing the logical capture channel input.
@@ -354,7 +366,7 @@ impl Info {
         }
     }
 
-    fn pwm_get_clock_freq(&self) -> u32 {
+    fn pwm_get_clock_freq(&self) -> Result<u32> {
         // SAFETY: This has no safety impact as we are getting a singleton register instance here and its dropped it the end of the function
         let reg = unsafe { Clkctl1::steal() };
 
@@ -362,18 +374,43 @@ impl Info {
         let mut freq: u32 = 0;
 
         if let Some(clk) = clksel {
+            // The selected source may have been powered down after init, in which case the
+            // configured rate no longer reflects what the CTimer is actually receiving.
+            let source = match clk {
+                Sel::MainClk => Some(Clocks::MainClk),
+                Sel::SfroClk => Some(Clocks::Sfro),
+                Sel::FfroClk => Some(Clocks::Ffro),
+                Sel::Lposc => Some(Clocks::Lposc),
+                _ => None,
+            };
+            if source.is_some_and(|source| !is_clock_running(source)) {
+                return Err(Error::ClockSourceDisabled);
+            }
+
             match clk {
                 Sel::MainClk => {
-                    freq = ClockConfig::crystal().main_clk.get_clock_rate().unwrap();
+                    freq = ClockConfig::crystal()
+                        .main_clk
+                        .ensure_main_clk()
+                        .map_err(|_| Error::ClockSourceDisabled)?;
                 }
                 Sel::SfroClk => {
-                    freq = ClockConfig::crystal().sfro.get_clock_rate().unwrap();
+                    freq = ClockConfig::crystal()
+                        .sfro
+                        .get_clock_rate()
+                        .map_err(|_| Error::ClockSourceDisabled)?;
                 }
                 Sel::FfroClk => {
-                    freq = ClockConfig::crystal().ffro.get_clock_rate().unwrap();
+                    freq = ClockConfig::crystal()
+                        .ffro
+                        .get_clock_rate()
+                        .map_err(|_| Error::ClockSourceDisabled)?;
                 }
                 Sel::Lposc => {
-                    freq = ClockConfig::crystal().lposc.get_clock_rate().unwrap();
+                    freq = ClockConfig::crystal()
+                        .lposc
+                        .get_clock_rate()
+                        .map_err(|_| Error::ClockSourceDisabled)?;
                 }
                 //TODO: Add get clock frequency for clock sources audio pll, mclk_in
                 _ => {
@@ -381,7 +418,7 @@ impl Info {
                 }
             }
         }
-        freq
+        Ok(freq)
     }
 
     fn pwm_configure(&self, period: u32) {
@@ -551,26 +588,29 @@ impl<M: Mode, P: CaptureEvent> CaptureTimer<'_, M, P> {
 
 impl<'p, P: CaptureEvent> CaptureTimer<'p, Async, P> {
     /// Creates a new `CaptureTimer` in asynchronous mode.
+    ///
+    /// Errors with [`Error::ClockSourceDisabled`] if `clk` isn't running.
     pub fn new_async<T: Instance>(
         _inst: Peri<'p, T>,
         pin: Peri<'p, P>,
         clk: impl ConfigurableClock,
         _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'p,
-    ) -> Self {
+    ) -> Result<Self> {
         let info = T::info();
         let module = info.module;
+        let clk_freq = clk.get_clock_rate().map_err(|_| Error::ClockSourceDisabled)?;
 
         T::Interrupt::unpend();
         unsafe { T::Interrupt::enable() };
 
-        Self {
+        Ok(Self {
             id: COUNT_CHANNEL + module * CHANNEL_PER_MODULE + info.channel,
             event_clock_counts: 0,
-            clk_freq: clk.get_clock_rate().unwrap(),
+            clk_freq,
             _phantom: core::marker::PhantomData,
             info,
             event_pin: pin,
-        }
+        })
     }
 
     /// Waits asynchronously for the capture timer to record an event timestamp.
@@ -640,18 +680,24 @@ impl<'p, P: CaptureEvent> CaptureTimer<'p, Async, P> {
 
 impl<'p, P: CaptureEvent> CaptureTimer<'p, Blocking, P> {
     /// Creates a new `CaptureTimer` in blocking mode.
-    pub fn new_blocking<T: Instance>(_inst: Peri<'p, T>, pin: Peri<'p, P>, clk: impl ConfigurableClock) -> Self {
+    ///
+    /// Errors with [`Error::ClockSourceDisabled`] if `clk` isn't running.
+    pub fn new_blocking<T: Instance>(
+        _inst: Peri<'p, T>,
+        pin: Peri<'p, P>,
+        clk: impl ConfigurableClock,
+    ) -> Result<Self> {
         let info = T::info();
         let module = info.module;
 
-        Self {
+        Ok(Self {
             id: COUNT_CHANNEL + module * CHANNEL_PER_MODULE + info.channel,
             event_clock_counts: 0,
-            clk_freq: clk.get_clock_rate().unwrap(),
+            clk_freq: clk.get_clock_rate().map_err(|_| Error::ClockSourceDisabled)?,
             _phantom: core::marker::PhantomData,
             info,
             event_pin: pin,
-        }
+        })
     }
     /// Waits synchronously for the capture timer
     /// This API can capture time till the counter has not crossed the original position after rollover
@@ -750,23 +796,26 @@ impl<'p, M: Mode> CountingTimer<'p, M> {
 
 impl<'p> CountingTimer<'p, Async> {
     /// Creates a new `CountingTimer` in asynchronous mode.
+    ///
+    /// Errors with [`Error::ClockSourceDisabled`] if `clk` isn't running.
     pub fn new_async<T: Instance>(
         _inst: Peri<'p, T>,
         clk: impl ConfigurableClock,
         _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'p,
-    ) -> Self {
+    ) -> Result<Self> {
         let info = T::info();
+        let clk_freq = clk.get_clock_rate().map_err(|_| Error::ClockSourceDisabled)?;
 
         T::Interrupt::unpend();
         unsafe { T::Interrupt::enable() };
 
-        Self {
+        Ok(Self {
             id: info.module * CHANNEL_PER_MODULE + info.channel,
-            clk_freq: clk.get_clock_rate().unwrap(),
+            clk_freq,
             timeout: 0,
             _phantom: core::marker::PhantomData,
             info,
-        }
+        })
     }
     /// Waits asynchronously for the countdown timer to complete.
     pub fn wait_us(&mut self, count_us: u32) -> impl Future<Output = ()> + use<'_, 'p> {
@@ -787,16 +836,18 @@ impl<'p> CountingTimer<'p, Async> {
 
 impl<'p> CountingTimer<'p, Blocking> {
     /// Creates a new `CountingTimer` in blocking mode.
-    pub fn new_blocking<T: Instance>(_inst: Peri<'p, T>, clk: impl ConfigurableClock) -> Self {
+    ///
+    /// Errors with [`Error::ClockSourceDisabled`] if `clk` isn't running.
+    pub fn new_blocking<T: Instance>(_inst: Peri<'p, T>, clk: impl ConfigurableClock) -> Result<Self> {
         let info = T::info();
 
-        Self {
+        Ok(Self {
             id: info.module * CHANNEL_PER_MODULE + info.channel,
-            clk_freq: clk.get_clock_rate().unwrap(),
+            clk_freq: clk.get_clock_rate().map_err(|_| Error::ClockSourceDisabled)?,
             timeout: 0,
             _phantom: core::marker::PhantomData,
             info,
-        }
+        })
     }
 
     /// Waits synchronously for the countdown timer to complete.
@@ -982,7 +1033,15 @@ impl embedded_hal_02::Pwm for CTimerPwm<'_> {
         // Updating period for one channel will impact all channels configured for PWM on the same timer
         // Period update also updates duty cycle which can cause an out of spec pulse in PWM output(output could stay low for a PWM period
         // before new duty cycle is updated)
-        let clock_rate = Hertz(self.info.pwm_get_clock_freq());
+        // The embedded-hal signature can't return the error, so keep the current period rather
+        // than dividing by the rate of a gated source
+        let clock_rate = match self.info.pwm_get_clock_freq() {
+            Ok(rate) => Hertz(rate),
+            Err(e) => {
+                error!("CTimer PWM period unchanged, clock source unavailable: {:?}", e);
+                return;
+            }
+        };
 
         let requested_pwm_rate: Hertz = period.into().into();
 
@@ -1017,6 +1076,80 @@ impl embedded_hal_02::Pwm for CTimerPwm<'_> {
 /// shorthand for -> Result<T>
 pub type Result<T> = core::result::Result<T, Error>;
 
+/// Computes the `CTimer` prescale register value needed to tick at `target_tick` from `source`.
+///
+/// The timer counter increments every `prescale + 1` source clocks, so the returned prescale
+/// is the one whose tick rate is closest to `target_tick`, alongside the tick rate actually
+/// achieved. A target at or above the source rate yields a prescale of 0, i.e. the counter
+/// runs at the source rate.
+pub fn prescaler_for(source: Hertz, target_tick: Hertz) -> Result<(u32, Hertz)> {
+    if source.0 == 0 || target_tick.0 == 0 {
+        return Err(Error::InvalidTickRate);
+    }
+
+    if target_tick.0 >= source.0 {
+        return Ok((0, source));
+    }
+
+    // round to the nearest divider rather than truncating
+    let div = ((u64::from(source.0) + u64::from(target_tick.0) / 2) / u64::from(target_tick.0)).max(1) as u32;
+
+    Ok((div - 1, Hertz(source.0 / div)))
+}
+
+/// `CTimer` function clock sources in order of stability, see [`most_stable_source`]
+const STABLE_SOURCES: [Clocks; 3] = [Clocks::Sfro, Clocks::MainClk, Clocks::Lposc];
+
+/// Rate of a `CTimer` function clock source under `config`
+fn source_rate(source: Clocks, config: &ClockConfig) -> Result<Hertz> {
+    let rate = match source {
+        Clocks::Sfro => config.sfro.get_clock_rate(),
+        Clocks::MainClk => config.main_clk.ensure_main_clk(),
+        Clocks::Lposc => config.lposc.get_clock_rate(),
+        _ => return Err(Error::ClockSourceDisabled),
+    };
+    rate.map(Hertz).map_err(|_| Error::ClockSourceDisabled)
+}
+
+/// Picks the most stable running `CTimer` function clock source, e.g. for a time driver tick.
+///
+/// SFRO is trimmed to 16 MHz and independent of the main clock, so it comes first. The main
+/// clock is only as accurate as whatever feeds it, and LPOSC's 1 MHz drifts by several percent,
+/// so it is the last resort. Returns the source with its rate, or `None` if none is running.
+pub fn most_stable_source(config: &ClockConfig) -> Option<(Clocks, Hertz)> {
+    STABLE_SOURCES
+        .into_iter()
+        .filter(|&source| is_clock_running(source))
+        .find_map(|source| source_rate(source, config).ok().map(|rate| (source, rate)))
+}
+
+/// Routes `source` to the function clock of `CTimer` `module` and returns its rate.
+///
+/// Only SFRO, the main clock and LPOSC are supported, and the source must be running.
+/// Errors with [`Error::InvalidArgument`] for any other source or a `module` above 4, and with
+/// [`Error::ClocksLocked`] after [`crate::clocks::lock_clocks`].
+pub fn set_source(module: usize, source: Clocks, config: &ClockConfig) -> Result<Hertz> {
+    check_unlocked().map_err(|_| Error::ClocksLocked)?;
+    let sel = match source {
+        Clocks::Sfro => Sel::SfroClk,
+        Clocks::MainClk => Sel::MainClk,
+        Clocks::Lposc => Sel::Lposc,
+        _ => return Err(Error::InvalidArgument),
+    };
+    if module >= COUNT_CHANNEL / CHANNEL_PER_MODULE {
+        return Err(Error::InvalidArgument);
+    }
+    if !is_clock_running(source) {
+        return Err(Error::ClockSourceDisabled);
+    }
+    let rate = source_rate(source, config)?;
+
+    // SAFETY: This has no safety impact as we are getting a singleton register instance here and its dropped it the end of the function
+    let reg = unsafe { Clkctl1::steal() };
+    reg.ct32bitfclksel(module).write(|w| w.sel().variant(sel));
+    Ok(rate)
+}
+
 impl<'p> CTimerPwm<'p> {
     /// Take the `CTimer` instance supplied and use it as a simple PWM driver. Function returns constructed Pwm instance.
     pub fn new<T: Instance>(
@@ -1049,7 +1182,7 @@ impl<'p> CTimerPwmPeriodChannel<'p> {
     pub fn new<T: Instance>(_length_channel: Peri<'p, T>, period: MicroSeconds) -> Result<Self> {
         let channel_info = T::info();
 
-        let clock_rate = Hertz(channel_info.pwm_get_clock_freq());
+        let clock_rate = Hertz(channel_info.pwm_get_clock_freq()?);
 
         let requested_pwm_rate: Hertz = period.into();
 
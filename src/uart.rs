@@ -302,7 +302,8 @@ impl<'a, M: Mode> Uart<'a, M> {
         cts: Option<Peri<'a, AnyPin>>,
         config: Config,
     ) -> Result<FlexcommRef> {
-        let flexcomm = T::enable(config.clock);
+        // an unclocked USART can't transfer anything
+        let flexcomm = T::enable(config.clock).map_err(|_| Error::InvalidArgument)?;
         T::into_usart();
 
         let regs = T::info().regs;
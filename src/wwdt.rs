@@ -4,7 +4,7 @@ use core::marker::PhantomData;
 
 use embassy_hal_internal::{Peri, PeripheralType};
 
-use crate::clocks::{SysconPeripheral, enable_and_reset};
+use crate::clocks::{Clocks, SysconPeripheral, enable_and_reset, ensure_running};
 use crate::peripherals::{WDT0, WDT1};
 
 /// Windowed watchdog timer (WWDT) driver.
@@ -43,7 +43,10 @@ impl SealedInstance for crate::peripherals::WDT0 {
     }
 
     fn init() {
-        init_lposc();
+        // Without LPOSC the watchdog never counts, so make the failure visible
+        if let Err(e) = ensure_running(Clocks::Lposc) {
+            error!("unable to start LPOSC for the watchdog: {:?}", e);
+        }
 
         // REVISIT: Can we do this generically?
         let clkctl0 = unsafe { &*crate::pac::Clkctl0::ptr() };
@@ -67,7 +70,10 @@ impl SealedInstance for crate::peripherals::WDT1 {
     }
 
     fn init() {
-        init_lposc();
+        // Without LPOSC the watchdog never counts, so make the failure visible
+        if let Err(e) = ensure_running(Clocks::Lposc) {
+            error!("unable to start LPOSC for the watchdog: {:?}", e);
+        }
 
         // Enable WWDT1 clock and set LPOSC as clock source
         let clkctl1 = unsafe { &*crate::pac::Clkctl1::ptr() };
@@ -106,18 +112,6 @@ const fn counter_to_time(counter: u32) -> u32 {
     (counter + 1) * (US_PER_TICK * PSC)
 }
 
-/// Initializes low-power oscillator.
-fn init_lposc() {
-    // Enable low power oscillator
-    let sysctl0 = unsafe { &*crate::pac::Sysctl0::ptr() };
-    sysctl0.pdruncfg0_clr().write(|w| w.lposc_pd().set_bit());
-
-    // Wait for low-power oscillator to be ready (typically 64 us)
-    // Busy loop seems better here than trying to shoe-in an async delay
-    let clkctl0 = unsafe { &*crate::pac::Clkctl0::ptr() };
-    while clkctl0.lposcctl0().read().clkrdy().bit_is_clear() {}
-}
-
 impl<'d> WindowedWatchdog<'d> {
     /// Creates a WWDT (Windowed Watchdog Timer) instance with a given timeout value in microseconds.
     ///